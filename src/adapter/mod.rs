@@ -1,10 +1,12 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
+use crate::domain::ConvertNytBoardError;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PositionDto(pub usize, pub usize);
 
@@ -19,9 +21,57 @@ pub struct NYTBoardDto {
     pub starting_board: Vec<String>,
     pub solutions: Vec<String>,
     pub theme_coords: HashMap<String, Vec<PositionDto>>,
+    pub spangram_coords: Vec<PositionDto>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NytClientError {
+    NotFound(NaiveDate),
+    Network(String),
+    Timeout,
+    Status(u16),
+    Schema(String),
+    /// The puzzle parsed but doesn't make a playable board.
+    InvalidBoard(ConvertNytBoardError),
+}
+
+impl NytClientError {
+    /// Whether the same request might succeed if tried again.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            NytClientError::Network(_) | NytClientError::Timeout => true,
+            NytClientError::Status(status) => *status == 429 || *status >= 500,
+            NytClientError::NotFound(_)
+            | NytClientError::Schema(_)
+            | NytClientError::InvalidBoard(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for NytClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NytClientError::NotFound(date) => {
+                write!(f, "no puzzle has been published for {}", date)
+            }
+            NytClientError::Network(message) => write!(f, "could not reach the NYT: {}", message),
+            NytClientError::Timeout => write!(f, "the NYT took too long to respond"),
+            NytClientError::Status(status) => write!(f, "the NYT responded with HTTP {}", status),
+            NytClientError::Schema(message) => {
+                write!(
+                    f,
+                    "the NYT puzzle was not in the expected format: {}",
+                    message
+                )
+            }
+            NytClientError::InvalidBoard(error) => {
+                write!(f, "the NYT puzzle could not be played: {}", error)
+            }
+        }
+    }
 }
 
 #[async_trait]
 pub trait NytClient {
-    async fn by_date(&self, date: &NaiveDate) -> NYTBoardDto;
+    async fn by_date(&self, date: &NaiveDate) -> Result<NYTBoardDto, NytClientError>;
 }
@@ -1,6 +1,8 @@
-use crate::domain::{ContiguousPositions, Guess};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+use crate::domain::{ContiguousPositions, Dimensions, Guess};
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct AnswerId(pub u32);
 
 impl AnswerId {
@@ -9,13 +11,13 @@ impl AnswerId {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub enum AnswerType {
     Normal,
     Spangram,
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct Answer {
     pub id: AnswerId,
     pub answer_type: AnswerType,
@@ -29,13 +31,14 @@ impl Answer {
         id: AnswerId,
         answer_type: AnswerType,
         positions: ContiguousPositions,
+        word: &str,
         order: u32,
     ) -> Self {
         Answer {
             id,
             answer_type,
             positions,
-            word: "Hello".to_string(),
+            word: word.to_string(),
             order,
         }
     }
@@ -43,4 +46,9 @@ impl Answer {
     pub fn matches_guess(&self, guess: &Guess) -> bool {
         self.positions == guess.positions
     }
+
+    /// Whether the answer reaches two opposite sides of a board with these dimensions.
+    pub fn spans(&self, dimensions: &Dimensions) -> bool {
+        self.positions.spans(dimensions)
+    }
 }
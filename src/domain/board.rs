@@ -1,16 +1,17 @@
-use std::collections::HashSet;
+use std::{collections::HashMap, fmt, iter};
 
 use chrono::NaiveDate;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    adapter::NYTBoardDto,
     domain::{Answer, AnswerId, Clue, Guess, Position},
-    NYTBoardDto,
 };
 
-use super::{AnswerType, ContiguousPositions};
+use super::{AnswerType, ContiguousPositions, CreateContiguousTilesError, MIN_WORD_LENGTH};
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct BoardId(pub u32);
 
 impl BoardId {
@@ -19,10 +20,10 @@ impl BoardId {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct Tile(pub char);
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct Tiles(pub Vec<Vec<Tile>>);
 
 impl Tiles {
@@ -31,18 +32,24 @@ impl Tiles {
     }
     pub fn from_strings(strings: &[String]) -> Self {
         let tiles: Vec<Vec<Tile>> = strings
-            .into_iter()
-            .map(|row| row.chars().map(move |letter| Tile(letter)).collect_vec())
+            .iter()
+            .map(|row| row.chars().map(Tile).collect_vec())
             .collect_vec();
 
         Tiles::new(tiles)
     }
     pub fn at_position(&self, position: &Position) -> Option<Tile> {
         let row: usize = position.row.try_into().ok()?;
-        let col: usize = position.row.try_into().ok()?;
+        let col: usize = position.col.try_into().ok()?;
 
         self.0.get(row)?.get(col).copied()
     }
+    pub fn dimensions(&self) -> Dimensions {
+        Dimensions {
+            width: self.0.first().map_or(0, |row| row.len()),
+            height: self.0.len(),
+        }
+    }
     pub fn all_positions(&self) -> Vec<Position> {
         self.0
             .iter()
@@ -63,7 +70,7 @@ impl Tiles {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct Board {
     pub id: BoardId,
     pub print_date: NaiveDate,
@@ -74,7 +81,7 @@ pub struct Board {
     dimensions: Dimensions,
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct Dimensions {
     pub width: usize,
     pub height: usize,
@@ -89,15 +96,10 @@ impl Board {
         answers: Vec<Answer>,
         tiles: Tiles,
         dimensions: Dimensions,
-    ) -> Result<Self, InvalidBoard> {
-        let answer_tiles_set: HashSet<Position> = answers
-            .iter()
-            .flat_map(|a| a.positions.inner_value())
-            .collect();
-        let actual_tiles_set: HashSet<Position> = tiles.all_positions().into_iter().collect();
-
-        if answer_tiles_set.eq(&actual_tiles_set) {
-            return Err(InvalidBoard::AnswersDontCoverAllTiles);
+    ) -> Result<Self, Vec<InvalidBoard>> {
+        let violations = Self::violations(&answers, &tiles, &dimensions);
+        if !violations.is_empty() {
+            return Err(violations);
         }
 
         Ok(Board {
@@ -118,18 +120,18 @@ impl Board {
         print_date: NaiveDate,
         answers: Vec<Answer>,
         tiles: &[String],
-    ) -> Result<Self, InvalidBoard> {
+    ) -> Result<Self, Vec<InvalidBoard>> {
         let height = tiles.len();
         let width = tiles
             .first()
-            .map(|row| row.len())
-            .ok_or(InvalidBoard::InconsistentDimensions)?;
+            .map(|row| row.chars().count())
+            .ok_or(vec![InvalidBoard::InconsistentDimensions])?;
         if tiles
             .iter()
-            .map(|row| row.len())
+            .map(|row| row.chars().count())
             .any(|row_width| row_width != width)
         {
-            return Err(InvalidBoard::InconsistentDimensions);
+            return Err(vec![InvalidBoard::InconsistentDimensions]);
         }
 
         let tiles: Tiles = Tiles::from_strings(&tiles.iter().map(|t| t.to_string()).collect_vec());
@@ -145,6 +147,70 @@ impl Board {
         )
     }
 
+    /// Checks every rule a playable board must follow, reporting all of the ones it breaks.
+    fn violations(answers: &[Answer], tiles: &Tiles, dimensions: &Dimensions) -> Vec<InvalidBoard> {
+        let mut violations = vec![];
+
+        let mut claimed_by: HashMap<Position, Vec<AnswerId>> = HashMap::new();
+        for answer in answers {
+            for position in answer.positions.iter() {
+                claimed_by
+                    .entry(position.clone())
+                    .or_default()
+                    .push(answer.id.clone());
+            }
+        }
+        let uncovered = tiles
+            .all_positions()
+            .into_iter()
+            .filter(|position| !claimed_by.contains_key(position))
+            .collect_vec();
+        if !uncovered.is_empty() {
+            violations.push(InvalidBoard::UncoveredTiles(uncovered));
+        }
+        for (position, answer_ids) in claimed_by.into_iter().sorted() {
+            if answer_ids.len() > 1 {
+                violations.push(InvalidBoard::OverlappingAnswers {
+                    position,
+                    answer_ids,
+                });
+            }
+        }
+
+        let spangrams = answers
+            .iter()
+            .filter(|answer| answer.answer_type == AnswerType::Spangram)
+            .collect_vec();
+        match spangrams.as_slice() {
+            [] => violations.push(InvalidBoard::MissingSpangram),
+            [spangram] if !spangram.spans(dimensions) => {
+                violations.push(InvalidBoard::SpangramDoesNotSpan(spangram.id.clone()))
+            }
+            [_] => {}
+            _ => violations.push(InvalidBoard::MultipleSpangrams(
+                spangrams.iter().map(|answer| answer.id.clone()).collect(),
+            )),
+        }
+
+        for answer in answers {
+            if answer.positions.len() < MIN_WORD_LENGTH {
+                violations.push(InvalidBoard::WordTooShort(answer.id.clone()));
+            }
+            match tiles.get_word(&answer.positions) {
+                None => violations.push(InvalidBoard::AnswerOutOfBounds(answer.id.clone())),
+                Some(spelled) if spelled.to_lowercase() != answer.word.to_lowercase() => violations
+                    .push(InvalidBoard::WordMismatch {
+                        answer_id: answer.id.clone(),
+                        word: answer.word.clone(),
+                        spelled,
+                    }),
+                Some(_) => {}
+            }
+        }
+
+        violations
+    }
+
     pub fn spangram(&self) -> &Answer {
         self.answers
             .iter()
@@ -177,41 +243,111 @@ impl Board {
     pub fn get_word(&self, positions: &ContiguousPositions) -> Option<String> {
         self.tiles.get_word(positions)
     }
+
+    pub fn answer_at(&self, position: &Position) -> Option<&Answer> {
+        self.answers
+            .iter()
+            .find(|answer| answer.positions.iter().any(|p| p == position))
+    }
 }
 
-impl From<NYTBoardDto> for Board {
-    fn from(nyt: NYTBoardDto) -> Self {
-        let normal_answers: Vec<Answer> = nyt
+impl TryFrom<NYTBoardDto> for Board {
+    type Error = ConvertNytBoardError;
+
+    fn try_from(nyt: NYTBoardDto) -> Result<Self, Self::Error> {
+        // `theme_coords` has no stable order, so number answers the way `solutions` lists them.
+        let solutions = nyt.solutions;
+        let theme_words = nyt
             .theme_coords
             .into_iter()
+            .sorted_by_key(|(word, _)| (solutions.iter().position(|s| s == word), word.clone()))
+            .map(|(word, coords)| (word, coords, AnswerType::Normal));
+        let spangram = (nyt.spangram, nyt.spangram_coords, AnswerType::Spangram);
+
+        let answers = theme_words
+            .chain(iter::once(spangram))
             .enumerate()
-            .map(|(index, (word, positions))| Answer {
-                id: AnswerId(index.try_into().unwrap()),
-                answer_type: AnswerType::Normal,
-                positions: ContiguousPositions::new(
-                    positions.into_iter().map(|p| p.into()).collect_vec(),
-                )
-                .unwrap(),
-                word,
-                order: index.try_into().unwrap(),
+            .map(|(index, (word, coords, answer_type))| {
+                let positions = coords
+                    .into_iter()
+                    .map(Position::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| ConvertNytBoardError::PositionOutOfRange { word: word.clone() })?;
+                let positions = ContiguousPositions::new(positions).map_err(|error| {
+                    ConvertNytBoardError::BrokenPath {
+                        word: word.clone(),
+                        error,
+                    }
+                })?;
+                let index: u32 = index.try_into().unwrap();
+                Ok(Answer {
+                    id: AnswerId(index),
+                    answer_type,
+                    positions,
+                    word,
+                    order: index,
+                })
             })
-            .collect();
+            .collect::<Result<Vec<Answer>, ConvertNytBoardError>>()?;
+
         Board::from_string(
             BoardId::new(nyt.id),
             nyt.editor,
             nyt.clue,
             nyt.print_date,
-            normal_answers,
+            answers,
             &nyt.starting_board,
         )
-        .unwrap()
+        .map_err(ConvertNytBoardError::InvalidBoard)
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConvertNytBoardError {
+    PositionOutOfRange {
+        word: String,
+    },
+    BrokenPath {
+        word: String,
+        error: CreateContiguousTilesError,
+    },
+    InvalidBoard(Vec<InvalidBoard>),
+}
+
+impl fmt::Display for ConvertNytBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertNytBoardError::PositionOutOfRange { word } => {
+                write!(f, "{} has a position off the board", word)
+            }
+            ConvertNytBoardError::BrokenPath { word, error } => {
+                write!(f, "the path for {} is broken: {:?}", word, error)
+            }
+            ConvertNytBoardError::InvalidBoard(violations) => {
+                write!(f, "the board breaks the rules: {:?}", violations)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum InvalidBoard {
     InconsistentDimensions,
-    AnswersDontCoverAllTiles,
+    UncoveredTiles(Vec<Position>),
+    OverlappingAnswers {
+        position: Position,
+        answer_ids: Vec<AnswerId>,
+    },
+    MissingSpangram,
+    MultipleSpangrams(Vec<AnswerId>),
+    SpangramDoesNotSpan(AnswerId),
+    AnswerOutOfBounds(AnswerId),
+    WordTooShort(AnswerId),
+    WordMismatch {
+        answer_id: AnswerId,
+        word: String,
+        spelled: String,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq)]
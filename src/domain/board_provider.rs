@@ -0,0 +1,213 @@
+use std::{fmt, sync::Arc};
+
+use chrono::NaiveDate;
+use futures::{stream, StreamExt};
+use itertools::Itertools;
+
+use crate::adapter::{NytClient, NytClientError};
+
+use super::{Board, BoardId, BoardRepository, StorageError};
+
+/// Serves boards from the repository, only asking the NYT for dates it has never stored.
+pub struct BoardProvider {
+    board_repository: Arc<dyn BoardRepository>,
+    nyt_client: Arc<dyn NytClient>,
+}
+
+impl BoardProvider {
+    pub fn new(board_repository: Arc<dyn BoardRepository>, nyt_client: Arc<dyn NytClient>) -> Self {
+        Self {
+            board_repository,
+            nyt_client,
+        }
+    }
+
+    pub async fn by_date(&self, date: &NaiveDate) -> Result<Board, BoardProviderError> {
+        if let Some(board) = self
+            .board_repository
+            .by_date(date)
+            .await
+            .map_err(BoardProviderError::Storage)?
+        {
+            return Ok(board);
+        }
+        let board: Board = self
+            .nyt_client
+            .by_date(date)
+            .await
+            .map_err(BoardProviderError::CouldNotFetch)?
+            .try_into()
+            .map_err(|error| {
+                BoardProviderError::CouldNotFetch(NytClientError::InvalidBoard(error))
+            })?;
+        self.board_repository
+            .insert(board.clone())
+            .await
+            .map_err(BoardProviderError::Storage)?;
+        Ok(board)
+    }
+
+    pub async fn by_id(&self, id: &BoardId) -> Result<Option<Board>, StorageError> {
+        self.board_repository.by_id(id).await
+    }
+
+    pub async fn insert(&self, board: Board) -> Result<(), StorageError> {
+        self.board_repository.insert(board).await
+    }
+
+    /// Stores every board printed between `from` and `to` inclusive, making at most
+    /// `concurrency` requests at once. Dates that are already stored are skipped, so an
+    /// interrupted run can simply be started again.
+    pub async fn fetch_range(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        concurrency: usize,
+    ) -> FetchSummary {
+        let dates = from.iter_days().take_while(|date| date <= &to);
+        let outcomes: Vec<(NaiveDate, FetchOutcome)> = stream::iter(dates)
+            .map(|date| async move { (date, self.fetch_if_missing(&date).await) })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut summary = FetchSummary::default();
+        for (date, outcome) in outcomes.into_iter().sorted_by_key(|(date, _)| *date) {
+            match outcome {
+                FetchOutcome::Fetched => summary.fetched.push(date),
+                FetchOutcome::AlreadyStored => summary.skipped.push(date),
+                FetchOutcome::Failed(error) => summary.failed.push((date, error)),
+            }
+        }
+        summary
+    }
+
+    async fn fetch_if_missing(&self, date: &NaiveDate) -> FetchOutcome {
+        match self.board_repository.by_date(date).await {
+            Ok(Some(_)) => return FetchOutcome::AlreadyStored,
+            Ok(None) => {}
+            Err(error) => return FetchOutcome::Failed(BoardProviderError::Storage(error)),
+        }
+        match self.by_date(date).await {
+            Ok(_) => FetchOutcome::Fetched,
+            Err(error) => FetchOutcome::Failed(error),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BoardProviderError {
+    CouldNotFetch(NytClientError),
+    Storage(StorageError),
+}
+
+impl fmt::Display for BoardProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardProviderError::CouldNotFetch(error) => write!(f, "{}", error),
+            BoardProviderError::Storage(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+enum FetchOutcome {
+    Fetched,
+    AlreadyStored,
+    Failed(BoardProviderError),
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FetchSummary {
+    pub fetched: Vec<NaiveDate>,
+    pub skipped: Vec<NaiveDate>,
+    pub failed: Vec<(NaiveDate, BoardProviderError)>,
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    use async_trait::async_trait;
+    use chrono::Datelike;
+
+    use super::*;
+    use crate::{
+        adapter::{NYTBoardDto, PositionDto},
+        infrastructure::InMemoryBoardRepository,
+    };
+
+    struct CountingNytClient(AtomicU32);
+
+    #[async_trait]
+    impl NytClient for CountingNytClient {
+        async fn by_date(&self, date: &NaiveDate) -> Result<NYTBoardDto, NytClientError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            if date.day() == 13 {
+                return Err(NytClientError::NotFound(*date));
+            }
+            Ok(NYTBoardDto {
+                id: date.ordinal(),
+                editor: "Chris".to_string(),
+                print_date: *date,
+                spangram: "EFGH".to_string(),
+                clue: "Try This".to_string(),
+                starting_board: vec!["ABCD".to_string(), "EFGH".to_string()],
+                solutions: vec!["ABCD".to_string(), "EFGH".to_string()],
+                theme_coords: HashMap::from([(
+                    "ABCD".to_string(),
+                    (0..4).map(|col| PositionDto(0, col)).collect(),
+                )]),
+                spangram_coords: (0..4).map(|col| PositionDto(1, col)).collect(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn fetches_each_date_once() {
+        let nyt_client = Arc::new(CountingNytClient(AtomicU32::new(0)));
+        let board_provider = BoardProvider::new(
+            Arc::new(InMemoryBoardRepository::default()),
+            nyt_client.clone(),
+        );
+        let date = NaiveDate::from_ymd_opt(2024, 4, 24).unwrap();
+
+        let fetched = board_provider.by_date(&date).await.unwrap();
+        let cached = board_provider.by_date(&date).await.unwrap();
+
+        assert_eq!(fetched, cached);
+        assert_eq!(nyt_client.0.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            board_provider.by_id(&BoardId::new(115)).await,
+            Ok(Some(fetched))
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_range_skips_stored_dates_and_reports_failures() {
+        let nyt_client = Arc::new(CountingNytClient(AtomicU32::new(0)));
+        let board_provider = BoardProvider::new(
+            Arc::new(InMemoryBoardRepository::default()),
+            nyt_client.clone(),
+        );
+        let date = |day| NaiveDate::from_ymd_opt(2024, 4, day).unwrap();
+        let _ = board_provider.by_date(&date(11)).await;
+
+        let summary = board_provider.fetch_range(date(10), date(14), 2).await;
+
+        assert_eq!(
+            summary,
+            FetchSummary {
+                fetched: vec![date(10), date(12), date(14)],
+                skipped: vec![date(11)],
+                failed: vec![(
+                    date(13),
+                    BoardProviderError::CouldNotFetch(NytClientError::NotFound(date(13)))
+                )],
+            }
+        );
+        assert_eq!(nyt_client.0.load(Ordering::SeqCst), 5);
+    }
+}
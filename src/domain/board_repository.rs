@@ -1,10 +1,39 @@
 use crate::domain::*;
 use async_trait::async_trait;
 use chrono::NaiveDate;
+use std::fmt;
 
 #[async_trait]
 pub trait BoardRepository {
-    async fn by_date(&self, date: &NaiveDate) -> Option<Board>;
-    async fn by_id(&self, id: &BoardId) -> Option<Board>;
-    async fn insert(&self, board: Board) -> ();
+    async fn by_date(&self, date: &NaiveDate) -> Result<Option<Board>, StorageError>;
+    async fn by_id(&self, id: &BoardId) -> Result<Option<Board>, StorageError>;
+    async fn insert(&self, board: Board) -> Result<(), StorageError>;
+}
+
+/// Why a repository couldn't read or write what it stores.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum StorageError {
+    /// The files or database behind the repository couldn't be used, for example because
+    /// they are read-only, full, locked or corrupt.
+    Unavailable(String),
+    /// A stored board no longer passes validation.
+    InvalidBoard(BoardId, Vec<InvalidBoard>),
+    /// A stored game's events can't be replayed.
+    InvalidGame(GameId, ReplayError),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Unavailable(message) => {
+                write!(f, "saved data could not be used: {}", message)
+            }
+            StorageError::InvalidBoard(id, violations) => {
+                write!(f, "stored puzzle {} is invalid: {:?}", id.0, violations)
+            }
+            StorageError::InvalidGame(id, error) => {
+                write!(f, "stored game {} is invalid: {}", id.as_str(), error)
+            }
+        }
+    }
 }
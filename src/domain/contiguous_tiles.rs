@@ -1,10 +1,11 @@
-use std::{collections::HashSet, slice::Iter};
+use std::collections::HashSet;
 
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
-use super::Position;
+use super::{Dimensions, Position};
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct ContiguousPositions(Vec<Position>);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -34,19 +35,32 @@ impl ContiguousPositions {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &Position> {
         self.0.iter()
     }
 
+    /// Whether the path reaches two opposite sides of a board with these dimensions.
+    pub fn spans(&self, dimensions: &Dimensions) -> bool {
+        let touches = |edge: fn(&Position) -> i32, far_edge: usize| {
+            let far_edge: i32 = far_edge.try_into().unwrap();
+            self.0.iter().any(|p| edge(p) == 0) && self.0.iter().any(|p| edge(p) == far_edge - 1)
+        };
+        touches(|p| p.row, dimensions.height) || touches(|p| p.col, dimensions.width)
+    }
+
     fn breaks_continuity(positions: &[Position]) -> bool {
         positions
             .iter()
-            .tuples()
+            .tuple_windows()
             .any(|(first, second)| !first.is_adjacent_to(second))
     }
 
     fn has_duplicates(positions: &[Position]) -> bool {
-        HashSet::<&Position>::from_iter(positions.into_iter()).len() != positions.len()
+        HashSet::<&Position>::from_iter(positions).len() != positions.len()
     }
 }
 
@@ -55,7 +69,7 @@ mod test {
     use super::*;
 
     #[test]
-    fn errors_for_duplicates() -> () {
+    fn errors_for_duplicates() {
         let duplicate_positions =
             ContiguousPositions::new(vec![Position::new(0, 0), Position::new(0, 0)]);
 
@@ -66,7 +80,7 @@ mod test {
     }
 
     #[test]
-    fn errors_for_disconnected() -> () {
+    fn errors_for_disconnected() {
         let disconnected_positions =
             ContiguousPositions::new(vec![Position::new(0, 0), Position::new(0, 2)]);
 
@@ -75,4 +89,19 @@ mod test {
             Err(CreateContiguousTilesError::Disconnected)
         )
     }
+
+    #[test]
+    fn errors_for_gap_between_pairs() {
+        let disconnected_positions = ContiguousPositions::new(vec![
+            Position::new(0, 0),
+            Position::new(0, 1),
+            Position::new(0, 3),
+            Position::new(0, 4),
+        ]);
+
+        assert_eq!(
+            disconnected_positions,
+            Err(CreateContiguousTilesError::Disconnected)
+        )
+    }
 }
@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+
+use unicode_normalization::UnicodeNormalization;
+
+pub trait Dictionary {
+    fn contains_word(&self, word: &str) -> bool;
+}
+
+/// A dictionary that can also tell whether any word starts with some letters, so searches
+/// over the board can stop following a path as soon as it can't spell anything.
+pub trait PrefixDictionary: Dictionary {
+    fn has_prefix(&self, prefix: &str) -> bool;
+}
+
+/// Puts a word in the form dictionaries store it, so that `"CAFÉ"` from the tiles matches
+/// `"café"` from a word list regardless of how either was composed.
+pub fn normalize_word(word: &str) -> String {
+    word.trim().nfc().flat_map(char::to_lowercase).collect()
+}
+
+/// A word list held in memory.
+///
+/// Words are layered: a base list plus an allowlist of extra words, minus a blocklist that
+/// wins over both.
+#[derive(Default)]
+pub struct HashSetDictionary {
+    words: HashSet<String>,
+    blocked: HashSet<String>,
+    min_length: usize,
+}
+
+impl HashSetDictionary {
+    pub fn new(words: HashSet<String>) -> Self {
+        Self::default().allow(words)
+    }
+
+    /// Leaves out words shorter than `min_length` letters, including ones added later.
+    pub fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self.words.retain(|word| word.chars().count() >= min_length);
+        self
+    }
+
+    pub fn allow<I>(mut self, words: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let min_length = self.min_length;
+        self.words.extend(
+            words
+                .into_iter()
+                .map(|word| normalize_word(word.as_ref()))
+                .filter(|word| !word.is_empty() && word.chars().count() >= min_length),
+        );
+        self
+    }
+
+    pub fn block<I>(mut self, words: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.blocked
+            .extend(words.into_iter().map(|word| normalize_word(word.as_ref())));
+        self
+    }
+
+    /// The words in no particular order, leaving out blocked ones.
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.words.difference(&self.blocked).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.difference(&self.blocked).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Dictionary for HashSetDictionary {
+    fn contains_word(&self, word: &str) -> bool {
+        let word = normalize_word(word);
+        self.words.contains(&word) && !self.blocked.contains(&word)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matches_regardless_of_case_and_composition() {
+        let dictionary = HashSetDictionary::default().allow(["cafe\u{301}", "Hello"]);
+
+        assert!(dictionary.contains_word("CAFÉ"));
+        assert!(dictionary.contains_word("hello"));
+        assert!(!dictionary.contains_word("world"));
+    }
+
+    #[test]
+    fn blocklist_wins_over_base_and_allowlist() {
+        let dictionary = HashSetDictionary::default()
+            .with_min_length(4)
+            .allow(["cat", "lion", "tiger"])
+            .block(["TIGER"])
+            .allow(["tiger", "puma"]);
+
+        assert!(!dictionary.contains_word("cat"));
+        assert!(dictionary.contains_word("lion"));
+        assert!(dictionary.contains_word("puma"));
+        assert!(!dictionary.contains_word("tiger"));
+        assert_eq!(dictionary.len(), 2);
+    }
+}
@@ -1,101 +1,672 @@
+use std::{collections::HashSet, fmt};
+
 use crate::domain::{
-    Answer, AnswerId, AnswerType, Board, BoardId, Clue, FoundAnswer, Guess, PlayerId,
+    Answer, AnswerId, AnswerType, Board, BoardId, Clue, CreateGuessError, FoundAnswer, GameEvent,
+    GameEventKind, GameRules, Guess, GuessOutcome, PlayerId, Position,
 };
 
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
 use super::Dictionary;
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct GameId(String);
 
+impl GameId {
+    pub fn new(id: &str) -> Self {
+        GameId(id.to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn for_player_and_board(player_id: &PlayerId, board_id: &BoardId) -> Self {
+        GameId(format!("{}-{}", player_id.0, board_id.0))
+    }
+}
+
+/// A player's attempt at a board. Its events are the only state that is saved; every other
+/// field is rebuilt by applying them in order.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Game {
+    pub id: GameId,
+    pub version: u32,
     pub board_id: BoardId,
+    events: Vec<GameEvent>,
     pub player_id: PlayerId,
-    pub active_clue: Option<Clue>,
-    pub clue_progress_counter: u32,
-    pub found_answer_ids: Vec<AnswerId>,
-    pub guesses: Vec<Guess>,
-    pub actions: Vec<GameAction>,
+    pub rules: GameRules,
+    active_clue: Option<Clue>,
+    clue_progress_counter: u32,
+    found_answer_ids: Vec<AnswerId>,
+    /// Answers shown to the player after they asked for them, rather than found.
+    revealed_answer_ids: Vec<AnswerId>,
+    /// Guesses that found an answer or a bonus word, so tracing them again is refused.
+    guesses: Vec<Guess>,
+    actions: Vec<GameAction>,
+    /// Non-theme dictionary words the player has found, lowercased, in the order found.
+    bonus_words: Vec<String>,
+    started_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+    /// When each answer was found, in the order they were found.
+    found_times: Vec<(AnswerId, DateTime<Utc>)>,
+    hint_times: Vec<DateTime<Utc>>,
+    /// Stretches between the player quitting mid-solve and coming back to the game.
+    paused_intervals: Vec<PausedInterval>,
+    paused_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct PausedInterval {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
 }
 
 impl Game {
-    pub fn new(board_id: BoardId, player_id: PlayerId) -> Self {
-        Game {
+    pub fn new(board_id: BoardId, player_id: PlayerId, rules: GameRules) -> Self {
+        let events = vec![GameEvent::now(GameEventKind::Created {
+            player_id: player_id.clone(),
+            rules: rules.clone(),
+        })];
+        Self::fold_all(board_id, player_id, rules, events)
+    }
+
+    /// Rebuilds the game `events` were recorded from while it was played on `board`.
+    pub fn replay(events: Vec<GameEvent>, board: &Board) -> Result<Self, ReplayError> {
+        Self::from_events(board.id.clone(), events)
+    }
+
+    /// Rebuilds a game from its events, which must begin with `GameEventKind::Created`.
+    pub fn from_events(board_id: BoardId, events: Vec<GameEvent>) -> Result<Self, ReplayError> {
+        let (player_id, rules) = match events.first().map(|event| &event.kind) {
+            Some(GameEventKind::Created { player_id, rules }) => (player_id.clone(), rules.clone()),
+            _ => return Err(ReplayError::NotCreatedFirst),
+        };
+        Ok(Self::fold_all(board_id, player_id, rules, events))
+    }
+
+    fn fold_all(
+        board_id: BoardId,
+        player_id: PlayerId,
+        rules: GameRules,
+        events: Vec<GameEvent>,
+    ) -> Self {
+        let mut game = Game {
+            id: GameId::for_player_and_board(&player_id, &board_id),
+            version: 0,
             board_id,
+            events,
             player_id,
+            rules,
             active_clue: None,
             clue_progress_counter: 0,
             found_answer_ids: vec![],
+            revealed_answer_ids: vec![],
             guesses: vec![],
             actions: vec![],
+            bonus_words: vec![],
+            started_at: None,
+            finished_at: None,
+            found_times: vec![],
+            hint_times: vec![],
+            paused_intervals: vec![],
+            paused_at: None,
+        };
+        game.refold();
+        game
+    }
+
+    fn record(&mut self, kind: GameEventKind) {
+        self.apply(GameEvent::now(kind));
+    }
+
+    fn apply(&mut self, event: GameEvent) {
+        let rewinds = matches!(event.kind, GameEventKind::Undone | GameEventKind::Restarted);
+        self.events.push(event.clone());
+        if rewinds {
+            self.refold();
+        } else {
+            self.fold(&event);
+        }
+    }
+
+    /// Rebuilds every field from the events that are still in effect.
+    fn refold(&mut self) {
+        self.active_clue = None;
+        self.clue_progress_counter = 0;
+        self.found_answer_ids.clear();
+        self.revealed_answer_ids.clear();
+        self.guesses.clear();
+        self.actions.clear();
+        self.bonus_words.clear();
+        self.started_at = None;
+        self.finished_at = None;
+        self.found_times.clear();
+        self.hint_times.clear();
+        self.paused_intervals.clear();
+        self.paused_at = None;
+        for event in effective_events(&self.events) {
+            self.fold(&event);
+        }
+    }
+
+    fn fold(&mut self, event: &GameEvent) {
+        match &event.kind {
+            GameEventKind::Created { .. } => {}
+            GameEventKind::GuessSubmitted { positions, outcome } => {
+                self.mark_active(event.at);
+                if !matches!(outcome, GuessOutcome::Rejected(_)) {
+                    self.guesses.push(Guess {
+                        positions: positions.clone(),
+                    });
+                }
+                match outcome {
+                    GuessOutcome::FoundAnswer(answer) => {
+                        self.found_times.push((answer.id.clone(), event.at));
+                        self.apply_found_answer(answer)
+                    }
+                    GuessOutcome::BonusWord(word) => {
+                        self.bonus_words.push(word.clone());
+                        self.clue_progress_counter += 1;
+                    }
+                    GuessOutcome::Rejected(_) => {}
+                }
+            }
+            GameEventKind::HintRedeemed(clue) => {
+                self.mark_active(event.at);
+                self.hint_times.push(event.at);
+                self.active_clue = Some(clue.clone());
+                self.actions.push(GameAction::Clue);
+                self.spend_hint_cost();
+            }
+            GameEventKind::HintOrderRevealed => {
+                self.mark_active(event.at);
+                self.hint_times.push(event.at);
+                if let Some(clue) = self.active_clue.as_mut() {
+                    clue.reveal_order();
+                }
+                self.actions.push(GameAction::ClueOrder);
+                self.spend_hint_cost();
+            }
+            GameEventKind::AnswerRevealed(answer) => {
+                self.mark_active(event.at);
+                self.actions.push(GameAction::AnswerRevealed);
+                self.revealed_answer_ids.push(answer.id.clone());
+                self.clear_clue_for(answer);
+            }
+            GameEventKind::Finished => {
+                self.mark_active(event.at);
+                self.finished_at = Some(event.at);
+            }
+            GameEventKind::Paused => {
+                if self.started_at.is_some() && self.finished_at.is_none() {
+                    self.paused_at.get_or_insert(event.at);
+                }
+            }
+            GameEventKind::Resumed => self.mark_active(event.at),
+            // Rewinds are resolved by `effective_events` before folding.
+            GameEventKind::Undone | GameEventKind::Restarted => {}
+        }
+    }
+
+    /// Starts the clock if the game was never opened and closes any pause the player came
+    /// back from.
+    fn mark_active(&mut self, at: DateTime<Utc>) {
+        if let Some(from) = self.paused_at.take() {
+            self.paused_intervals.push(PausedInterval { from, to: at });
+        }
+        if self.started_at.is_none() && self.finished_at.is_none() {
+            self.started_at = Some(at);
+        }
+    }
+
+    fn apply_found_answer(&mut self, answer: &Answer) {
+        match answer.answer_type {
+            AnswerType::Normal => self.actions.push(GameAction::NormalAnswerFound),
+            AnswerType::Spangram => self.actions.push(GameAction::SpanogramFound),
+        }
+        self.found_answer_ids.push(answer.id.clone());
+        self.clear_clue_for(answer);
+    }
+
+    fn clear_clue_for(&mut self, answer: &Answer) {
+        if self
+            .active_clue
+            .as_ref()
+            .is_some_and(|clue| clue.is_for(answer))
+        {
+            self.active_clue = None;
+        }
+    }
+
+    /// Whether the answer has been found or revealed.
+    pub fn is_resolved(&self, answer_id: &AnswerId) -> bool {
+        self.found_answer_ids.contains(answer_id) || self.revealed_answer_ids.contains(answer_id)
+    }
+
+    fn resolved_answer_ids(&self) -> Vec<AnswerId> {
+        self.found_answer_ids
+            .iter()
+            .chain(&self.revealed_answer_ids)
+            .cloned()
+            .collect()
+    }
+
+    fn finish_if_complete(&mut self, board: &Board) {
+        if board
+            .answers
+            .iter()
+            .all(|answer| self.is_resolved(&answer.id))
+        {
+            self.record(GameEventKind::Finished);
+        }
+    }
+
+    fn spend_hint_cost(&mut self) {
+        self.clue_progress_counter = self
+            .clue_progress_counter
+            .saturating_sub(self.rules.hint_cost);
+    }
+
+    pub fn active_clue(&self) -> Option<&Clue> {
+        self.active_clue.as_ref()
+    }
+
+    /// Bonus words found towards the next hint, less what earlier hints cost.
+    pub fn clue_progress_counter(&self) -> u32 {
+        self.clue_progress_counter
+    }
+
+    /// Everything that happened in the game, including moves that were later undone.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    pub fn found_answer_ids(&self) -> &[AnswerId] {
+        &self.found_answer_ids
+    }
+
+    /// Answers shown to the player after they asked for them, rather than found.
+    pub fn revealed_answer_ids(&self) -> &[AnswerId] {
+        &self.revealed_answer_ids
+    }
+
+    pub fn guesses(&self) -> &[Guess] {
+        &self.guesses
+    }
+
+    pub fn actions(&self) -> &[GameAction] {
+        &self.actions
+    }
+
+    pub fn bonus_words(&self) -> &[String] {
+        &self.bonus_words
+    }
+
+    pub fn state(&self) -> GameState {
+        match (self.started_at, self.finished_at) {
+            (_, Some(finished_at)) => GameState::Finished(self.summary(finished_at)),
+            (Some(_), None) => GameState::InProgress,
+            (None, None) => GameState::NotStarted,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    fn hints_used(&self) -> u32 {
+        let hints_used = self
+            .actions
+            .iter()
+            .filter(|action| action.is_hint())
+            .count();
+        hints_used.try_into().unwrap()
+    }
+
+    /// How long the player had spent solving at `until`, leaving out time spent paused.
+    pub fn active_time(&self, until: DateTime<Utc>) -> TimeDelta {
+        let Some(started_at) = self.started_at else {
+            return TimeDelta::zero();
+        };
+        let paused: TimeDelta = self
+            .paused_intervals
+            .iter()
+            .map(|interval| (interval.from, interval.to))
+            .chain(self.paused_at.map(|from| (from, until)))
+            .map(|(from, to)| to.min(until) - from.min(until))
+            .sum();
+        (until - started_at) - paused
+    }
+
+    fn summary(&self, finished_at: DateTime<Utc>) -> GameSummary {
+        GameSummary {
+            active_duration: self.active_time(finished_at),
+            answer_times: self
+                .found_times
+                .iter()
+                .map(|(answer_id, at)| (answer_id.clone(), self.active_time(*at)))
+                .collect(),
+            hint_times: self
+                .hint_times
+                .iter()
+                .map(|at| self.active_time(*at))
+                .collect(),
+            answers_found: self.found_answer_ids.len(),
+            answers_revealed: self.revealed_answer_ids.len(),
+            hints_used: self.hints_used(),
+            non_theme_words_found: self.bonus_words.len().try_into().unwrap(),
+            duration: finished_at - self.started_at.unwrap_or(finished_at),
+            restarts: self.times_restarted(),
+            gave_up: self.actions.last() == Some(&GameAction::AnswerRevealed),
+        }
+    }
+
+    /// Tiles that can still be used in a guess, which under the default rules excludes
+    /// those of answers the player has already found.
+    pub fn available_positions(&self, board: &Board) -> Vec<Position> {
+        let locked = self.locked_positions(board);
+        board
+            .tiles
+            .all_positions()
+            .into_iter()
+            .filter(|position| !locked.contains(position))
+            .collect()
+    }
+
+    fn locked_positions(&self, board: &Board) -> HashSet<Position> {
+        if !self.rules.found_tiles_lock {
+            return HashSet::new();
         }
+        board
+            .answers
+            .iter()
+            .filter(|answer| self.is_resolved(&answer.id))
+            .flat_map(|answer| answer.positions.iter().cloned())
+            .collect()
     }
 
     pub fn make_guess(
         &mut self,
         guess: Guess,
         board: &Board,
-        dictionary: &impl Dictionary,
+        dictionary: &dyn Dictionary,
     ) -> Result<GuessSuccess, GuessFailure> {
-        if self.guesses.contains(&guess) {
-            return Err(GuessFailure::AlreadyGuessed);
+        let outcome = self.judge_guess(&guess, board, dictionary);
+        self.record(GameEventKind::GuessSubmitted {
+            positions: guess.positions,
+            outcome: outcome.clone(),
+        });
+
+        match outcome {
+            GuessOutcome::FoundAnswer(answer) => {
+                self.finish_if_complete(board);
+                Ok(GuessSuccess::FoundAnswer(answer))
+            }
+            GuessOutcome::BonusWord(_) => Ok(GuessSuccess::GainedClue(self.clue_progress_counter)),
+            GuessOutcome::Rejected(failure) => Err(failure),
         }
+    }
 
-        let response = match board.guess_is_answer(&guess) {
-            FoundAnswer::Found(answer) => Ok(self.process_answer(answer)),
-            FoundAnswer::NotAnswer => self.check_matches_dictionary(board, &guess, dictionary),
-        };
+    fn judge_guess(
+        &self,
+        guess: &Guess,
+        board: &Board,
+        dictionary: &dyn Dictionary,
+    ) -> GuessOutcome {
+        if self.is_finished() {
+            return GuessOutcome::Rejected(GuessFailure::GameFinished);
+        }
+        // The guess may have been built under looser rules than this game is played with.
+        if let Err(error) = Guess::new(guess.positions.clone(), &self.rules) {
+            return GuessOutcome::Rejected(GuessFailure::BreaksRules(error));
+        }
+        if self.guesses.contains(guess) {
+            return GuessOutcome::Rejected(GuessFailure::AlreadyGuessed);
+        }
+        let locked = self.locked_positions(board);
+        if guess
+            .positions
+            .iter()
+            .any(|position| locked.contains(position))
+        {
+            return GuessOutcome::Rejected(GuessFailure::TilesAlreadyUsed);
+        }
 
-        self.guesses.push(guess);
-        response
+        match board.guess_is_answer(guess) {
+            FoundAnswer::Found(answer) if self.is_resolved(&answer.id) => {
+                GuessOutcome::Rejected(GuessFailure::AlreadyGuessed)
+            }
+            FoundAnswer::Found(answer) => GuessOutcome::FoundAnswer(answer),
+            FoundAnswer::NotAnswer => self.check_matches_dictionary(board, guess, dictionary),
+        }
     }
 
     fn check_matches_dictionary(
-        &mut self,
+        &self,
         board: &Board,
         guess: &Guess,
-        dictionary: &impl Dictionary,
-    ) -> Result<GuessSuccess, GuessFailure> {
-        let word = board
-            .get_word(&guess.positions)
-            .ok_or(GuessFailure::OutOfBounds)?;
-        if dictionary.contains_word(&word) {
-            self.clue_progress_counter += 1;
-            Ok(GuessSuccess::GainedClue(self.clue_progress_counter))
+        dictionary: &dyn Dictionary,
+    ) -> GuessOutcome {
+        let Some(word) = board.get_word(&guess.positions) else {
+            return GuessOutcome::Rejected(GuessFailure::OutOfBounds);
+        };
+        let word = word.to_lowercase();
+        // The same word traced along another path still only counts once.
+        if self.bonus_words.contains(&word) {
+            return GuessOutcome::Rejected(GuessFailure::AlreadyGuessed);
+        }
+        if !self.rules.bonus_words_need_dictionary || dictionary.contains_word(&word) {
+            GuessOutcome::BonusWord(word)
         } else {
-            Err(GuessFailure::NotRealWord)
+            GuessOutcome::Rejected(GuessFailure::NotRealWord)
         }
     }
 
-    fn process_answer(&mut self, found_answer: Answer) -> GuessSuccess {
-        match found_answer.answer_type {
-            AnswerType::Normal => self.actions.push(GameAction::NormalAnswerFound),
-            AnswerType::Spangram => self.actions.push(GameAction::SpanogramFound),
+    /// Redeeming a hint first shows which tiles make up the next theme word. Redeeming
+    /// again before that word is found shows the order its letters are spelled in.
+    pub fn redeem_clue(&mut self, board: &Board) -> Result<(), RedeemClueFailure> {
+        if self.is_finished() {
+            return Err(RedeemClueFailure::GameFinished);
         }
-        self.found_answer_ids.push(found_answer.id.clone());
-        GuessSuccess::FoundAnswer(found_answer)
+        if self
+            .active_clue
+            .as_ref()
+            .is_some_and(|clue| clue.is_order_revealed())
+        {
+            return Err(RedeemClueFailure::ClueFullyRevealed);
+        }
+        if self
+            .rules
+            .max_hints
+            .is_some_and(|max_hints| self.hints_used() >= max_hints)
+        {
+            return Err(RedeemClueFailure::HintLimitReached);
+        }
+        if self.clue_progress_counter < self.rules.hint_cost {
+            return Err(RedeemClueFailure::NotEnoughClueProgress);
+        }
+
+        if self.active_clue.is_some() {
+            self.record(GameEventKind::HintOrderRevealed);
+        } else {
+            let clue = board
+                .get_next_clue(&self.resolved_answer_ids())
+                .ok_or(RedeemClueFailure::CouldNotFindClue)?;
+            self.record(GameEventKind::HintRedeemed(clue));
+        }
+
+        Ok(())
     }
 
-    pub fn redeem_clue(&mut self, board: &Board) -> Result<(), RedeemClueFailure> {
-        if self.clue_progress_counter < 3 {
-            return Err(RedeemClueFailure::NotEnoughClueProgress);
+    /// Takes back the most recent found word, bonus word or hint. Rejected guesses didn't
+    /// change anything, so they are skipped over.
+    pub fn undo(&mut self) -> Result<(), UndoFailure> {
+        if !self.rules.allow_undo {
+            return Err(UndoFailure::NotAllowed);
+        }
+        if self.is_finished() {
+            return Err(UndoFailure::GameFinished);
+        }
+        if !effective_events(&self.events)
+            .iter()
+            .any(GameEvent::is_undoable)
+        {
+            return Err(UndoFailure::NothingToUndo);
+        }
+        self.record(GameEventKind::Undone);
+        Ok(())
+    }
+
+    /// Shows the player an answer they couldn't find. It counts towards finishing the
+    /// board, but is reported separately from the answers they found.
+    pub fn reveal_answer(
+        &mut self,
+        board: &Board,
+        answer_id: &AnswerId,
+    ) -> Result<(), RevealFailure> {
+        if self.is_finished() {
+            return Err(RevealFailure::GameFinished);
+        }
+        let answer = board
+            .answers
+            .iter()
+            .find(|answer| &answer.id == answer_id)
+            .ok_or(RevealFailure::UnknownAnswer)?;
+        if self.is_resolved(answer_id) {
+            return Err(RevealFailure::AlreadyResolved);
+        }
+
+        self.record(GameEventKind::AnswerRevealed(answer.clone()));
+        self.finish_if_complete(board);
+        Ok(())
+    }
+
+    /// Reveals every answer that hasn't been found yet, ending the game.
+    pub fn give_up(&mut self, board: &Board) -> Result<(), RevealFailure> {
+        if self.is_finished() {
+            return Err(RevealFailure::GameFinished);
+        }
+        for answer in &board.answers {
+            if !self.is_resolved(&answer.id) {
+                self.record(GameEventKind::AnswerRevealed(answer.clone()));
+            }
         }
-        let clue = board
-            .get_next_clue(&self.found_answer_ids)
-            .ok_or(RedeemClueFailure::CouldNotFindClue)?;
+        self.finish_if_complete(board);
+        Ok(())
+    }
+
+    /// Stops the clock, for when the player closes the game before finishing it.
+    pub fn pause(&mut self) {
+        if self.state() == GameState::InProgress && self.paused_at.is_none() {
+            self.record(GameEventKind::Paused);
+        }
+    }
 
-        self.actions.push(GameAction::Clue);
-        self.clue_progress_counter -= 3;
-        self.active_clue = Some(clue.clone());
+    /// Starts the clock when the player opens the game, or restarts it after a pause.
+    pub fn resume(&mut self) {
+        if self.clock_is_stopped() {
+            self.record(GameEventKind::Resumed);
+        }
+    }
+
+    /// Whether the game is waiting for the player to open it or come back to it.
+    pub fn clock_is_stopped(&self) -> bool {
+        !self.is_finished() && (self.started_at.is_none() || self.is_paused())
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
 
+    /// Starts the board over, with the clock running from now. The earlier attempt stays in
+    /// the event log.
+    pub fn restart(&mut self) -> Result<(), RestartFailure> {
+        if !self.rules.allow_undo {
+            return Err(RestartFailure::NotAllowed);
+        }
+        if self.is_finished() {
+            return Err(RestartFailure::GameFinished);
+        }
+        self.record(GameEventKind::Restarted);
+        self.resume();
         Ok(())
     }
+
+    pub fn times_restarted(&self) -> u32 {
+        let restarts = self
+            .events
+            .iter()
+            .filter(|event| event.kind == GameEventKind::Restarted)
+            .count();
+        restarts.try_into().unwrap()
+    }
+}
+
+/// The events still in effect once every undo and restart has been resolved.
+fn effective_events(events: &[GameEvent]) -> Vec<GameEvent> {
+    let mut effective: Vec<GameEvent> = vec![];
+    for event in events {
+        match event.kind {
+            GameEventKind::Undone => {
+                if let Some(index) = effective.iter().rposition(GameEvent::is_undoable) {
+                    effective.remove(index);
+                }
+            }
+            GameEventKind::Restarted => {
+                effective.retain(|event| matches!(event.kind, GameEventKind::Created { .. }))
+            }
+            _ => effective.push(event.clone()),
+        }
+    }
+    effective
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum RevealFailure {
+    UnknownAnswer,
+    AlreadyResolved,
+    GameFinished,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum UndoFailure {
+    NotAllowed,
+    NothingToUndo,
+    GameFinished,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum RestartFailure {
+    NotAllowed,
+    GameFinished,
+}
+
+/// Why a game couldn't be rebuilt from its events.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum ReplayError {
+    /// The first event isn't `GameEventKind::Created`, so there's no player or rules to start from.
+    NotCreatedFirst,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::NotCreatedFirst => {
+                write!(f, "its events don't begin with it being created")
+            }
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum RedeemClueFailure {
     NotEnoughClueProgress,
     CouldNotFindClue,
+    ClueFullyRevealed,
+    HintLimitReached,
+    GameFinished,
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
@@ -104,18 +675,31 @@ pub enum GuessSuccess {
     GainedClue(u32),
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub enum GuessFailure {
     AlreadyGuessed,
     OutOfBounds,
     NotRealWord,
+    TilesAlreadyUsed,
+    BreaksRules(CreateGuessError),
+    GameFinished,
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub enum GameAction {
+    /// A hint showing which tiles belong to the next theme word.
     Clue,
+    /// A second hint on the same word showing the order of its letters.
+    ClueOrder,
     NormalAnswerFound,
     SpanogramFound,
+    AnswerRevealed,
+}
+
+impl GameAction {
+    pub fn is_hint(&self) -> bool {
+        matches!(self, GameAction::Clue | GameAction::ClueOrder)
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
@@ -124,6 +708,24 @@ pub struct GameHistory(Vec<GameAction>);
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum GameState {
     NotStarted,
-    InProgress(Game),
-    Finished(Vec<GameAction>),
+    InProgress,
+    Finished(GameSummary),
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct GameSummary {
+    pub answers_found: usize,
+    pub answers_revealed: usize,
+    pub hints_used: u32,
+    pub non_theme_words_found: u32,
+    /// Wall-clock time from opening the puzzle to finishing it.
+    pub duration: TimeDelta,
+    /// Time spent solving, without the paused intervals.
+    pub active_duration: TimeDelta,
+    /// How far into the active solve each answer was found.
+    pub answer_times: Vec<(AnswerId, TimeDelta)>,
+    pub hint_times: Vec<TimeDelta>,
+    pub restarts: u32,
+    /// Whether the board was finished by revealing the last answers rather than finding them.
+    pub gave_up: bool,
 }
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Answer, Clue, ContiguousPositions, GameRules, GuessFailure, PlayerId};
+
+/// Something that happened in a game. A game's state is rebuilt by applying its events in order.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub struct GameEvent {
+    pub at: DateTime<Utc>,
+    pub kind: GameEventKind,
+}
+
+impl GameEvent {
+    pub fn now(kind: GameEventKind) -> Self {
+        Self {
+            at: Utc::now(),
+            kind,
+        }
+    }
+
+    pub fn is_undoable(&self) -> bool {
+        match &self.kind {
+            GameEventKind::GuessSubmitted { outcome, .. } => {
+                !matches!(outcome, GuessOutcome::Rejected(_))
+            }
+            GameEventKind::HintRedeemed(_) | GameEventKind::HintOrderRevealed => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum GameEventKind {
+    Created {
+        player_id: PlayerId,
+        rules: GameRules,
+    },
+    GuessSubmitted {
+        positions: ContiguousPositions,
+        outcome: GuessOutcome,
+    },
+    HintRedeemed(Clue),
+    HintOrderRevealed,
+    /// The player asked to be shown an answer instead of finding it.
+    AnswerRevealed(Answer),
+    Finished,
+    /// The player left the game. Time until the next event isn't counted as solving.
+    Paused,
+    /// The player opened the game or came back to it, which starts the clock.
+    Resumed,
+    /// Takes back the latest undoable event still in effect.
+    Undone,
+    /// Takes back every event since the game was created.
+    Restarted,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum GuessOutcome {
+    FoundAnswer(Answer),
+    BonusWord(String),
+    Rejected(GuessFailure),
+}
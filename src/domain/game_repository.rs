@@ -1,8 +1,40 @@
 use crate::domain::*;
 use async_trait::async_trait;
+use std::fmt;
 
 #[async_trait]
 pub trait GameRepository {
-    async fn by_player_and_board(&self, player_id: &PlayerId, board_id: &BoardId) -> Option<Game>;
-    async fn by_id(&self, id: &GameId) -> Option<Game>;
+    async fn by_player_and_board(
+        &self,
+        player_id: &PlayerId,
+        board_id: &BoardId,
+    ) -> Result<Option<Game>, StorageError>;
+    async fn by_id(&self, id: &GameId) -> Result<Option<Game>, StorageError>;
+    async fn list_by_player(&self, player_id: &PlayerId) -> Result<Vec<Game>, StorageError>;
+    async fn list_by_board(&self, board_id: &BoardId) -> Result<Vec<Game>, StorageError>;
+    async fn insert(&self, game: Game) -> Result<(), SaveGameError>;
+    /// Stores `game` only if nobody else has saved it since it was loaded, returning it with its
+    /// version bumped.
+    async fn update(&self, game: Game) -> Result<Game, SaveGameError>;
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SaveGameError {
+    AlreadyExists,
+    NotFound,
+    VersionConflict { expected: u32, actual: u32 },
+    Storage(StorageError),
+}
+
+impl fmt::Display for SaveGameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveGameError::AlreadyExists => write!(f, "the game already exists"),
+            SaveGameError::NotFound => write!(f, "the game could not be found"),
+            SaveGameError::VersionConflict { .. } => {
+                write!(f, "the game was changed in another session")
+            }
+            SaveGameError::Storage(error) => write!(f, "{}", error),
+        }
+    }
 }
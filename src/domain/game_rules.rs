@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Position, MIN_WORD_LENGTH};
+
+/// The house rules a game is played under. The default matches the NYT game.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameRules {
+    pub min_word_length: usize,
+    /// How many non-theme words each hint costs.
+    pub hint_cost: u32,
+    pub allow_diagonals: bool,
+    /// Whether tiles of found answers are locked out of later guesses.
+    pub found_tiles_lock: bool,
+    /// The most hints a player can redeem, counting both hint levels. `None` is unlimited.
+    pub max_hints: Option<u32>,
+    /// Whether a non-theme path must spell a dictionary word to count towards a hint.
+    pub bonus_words_need_dictionary: bool,
+    /// Whether moves can be undone and the board started over. Official daily plays turn
+    /// this off so results can't be polished.
+    pub allow_undo: bool,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            min_word_length: MIN_WORD_LENGTH,
+            hint_cost: 3,
+            allow_diagonals: true,
+            found_tiles_lock: true,
+            max_hints: None,
+            bonus_words_need_dictionary: true,
+            allow_undo: true,
+        }
+    }
+}
+
+impl GameRules {
+    pub fn are_adjacent(&self, first: &Position, second: &Position) -> bool {
+        if self.allow_diagonals {
+            first.is_adjacent_to(second)
+        } else {
+            first.is_orthogonally_adjacent_to(second)
+        }
+    }
+}
@@ -1,34 +1,248 @@
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
 use chrono::NaiveDate;
 
-use crate::ui;
+use crate::{
+    adapter::{NytClient, NytClientError},
+    ui,
+};
 
-use super::{Board, BoardRepository, GameRepository, PlayerId};
+use super::{
+    Board, BoardProvider, BoardProviderError, BoardRepository, Dictionary, Game, GameId,
+    GameRepository, GameRules, Guess, GuessFailure, GuessSuccess, PlayerId, Position,
+    RedeemClueFailure, RestartFailure, RevealFailure, SaveGameError, StorageError, UndoFailure,
+};
 
 pub struct GameService {
     game_repository: Arc<dyn GameRepository>,
-    board_repository: Arc<dyn BoardRepository>,
+    board_provider: BoardProvider,
+    dictionary: Arc<dyn Dictionary>,
+    rules: GameRules,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GameServiceError {
+    GameNotFound,
+    BoardNotFound,
+    CouldNotSave(SaveGameError),
+    CouldNotFetchBoard(NytClientError),
+    Storage(StorageError),
+}
+
+impl fmt::Display for GameServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameServiceError::GameNotFound => write!(f, "the game could not be found"),
+            GameServiceError::BoardNotFound => write!(f, "the puzzle could not be found"),
+            GameServiceError::CouldNotSave(error) => {
+                write!(f, "the game could not be saved: {}", error)
+            }
+            GameServiceError::CouldNotFetchBoard(error) => {
+                write!(f, "the puzzle could not be downloaded: {}", error)
+            }
+            GameServiceError::Storage(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<BoardProviderError> for GameServiceError {
+    fn from(error: BoardProviderError) -> Self {
+        match error {
+            BoardProviderError::CouldNotFetch(error) => GameServiceError::CouldNotFetchBoard(error),
+            BoardProviderError::Storage(error) => GameServiceError::Storage(error),
+        }
+    }
+}
+
+impl From<StorageError> for GameServiceError {
+    fn from(error: StorageError) -> Self {
+        GameServiceError::Storage(error)
+    }
+}
+
+pub struct GameUpdate<T> {
+    pub result: T,
+    pub view: ui::GameView,
 }
 
 impl GameService {
-    pub async fn insert_board(&mut self, board: Board) -> () {
-        let _ = self.board_repository.insert(board);
+    pub fn new(
+        game_repository: Arc<dyn GameRepository>,
+        board_repository: Arc<dyn BoardRepository>,
+        nyt_client: Arc<dyn NytClient>,
+        dictionary: Arc<dyn Dictionary>,
+    ) -> Self {
+        Self {
+            game_repository,
+            board_provider: BoardProvider::new(board_repository, nyt_client),
+            dictionary,
+            rules: GameRules::default(),
+        }
+    }
+
+    /// Starts new games under `rules` instead of the NYT rules. Games already in progress
+    /// keep the rules they were started with.
+    pub fn with_rules(mut self, rules: GameRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub async fn insert_board(&self, board: Board) -> Result<(), GameServiceError> {
+        self.board_provider
+            .insert(board)
+            .await
+            .map_err(GameServiceError::Storage)
     }
 
-    pub async fn build_real_response(
+    pub async fn start_or_resume(
         &self,
         date: &NaiveDate,
         player_id: &PlayerId,
-    ) -> Option<ui::Board> {
-        let board = self.board_repository.by_date(date).await?;
-        let game = self
+    ) -> Result<ui::GameView, GameServiceError> {
+        let board = self.board_provider.by_date(date).await?;
+        let mut game = match self
             .game_repository
             .by_player_and_board(player_id, &board.id)
-            .await?;
-        Some(ui::Board {
-            tiles: board.tiles.into(),
-            theme: board.clue,
+            .await?
+        {
+            Some(game) => game,
+            None => self.create(&board, player_id).await?,
+        };
+        if !game.clock_is_stopped() {
+            return Ok(ui::GameView::new(&board, &game));
+        }
+
+        // The clock runs from when the player has the puzzle in front of them.
+        game.resume();
+        self.save(game, &board, ()).await.map(|update| update.view)
+    }
+
+    async fn create(&self, board: &Board, player_id: &PlayerId) -> Result<Game, GameServiceError> {
+        let game = Game::new(board.id.clone(), player_id.clone(), self.rules.clone());
+        match self.game_repository.insert(game.clone()).await {
+            Ok(()) => Ok(game),
+            // Another session started this game first, so pick up theirs instead.
+            Err(SaveGameError::AlreadyExists) => self
+                .game_repository
+                .by_id(&game.id)
+                .await?
+                .ok_or(GameServiceError::GameNotFound),
+            Err(error) => Err(GameServiceError::CouldNotSave(error)),
+        }
+    }
+
+    pub async fn submit_guess(
+        &self,
+        game_id: &GameId,
+        guess: Guess,
+    ) -> Result<GameUpdate<Result<GuessSuccess, GuessFailure>>, GameServiceError> {
+        let (mut game, board) = self.load(game_id).await?;
+
+        let result = game.make_guess(guess, &board, self.dictionary.as_ref());
+
+        self.save(game, &board, result).await
+    }
+
+    pub async fn redeem_clue(
+        &self,
+        game_id: &GameId,
+    ) -> Result<GameUpdate<Result<(), RedeemClueFailure>>, GameServiceError> {
+        let (mut game, board) = self.load(game_id).await?;
+
+        let result = game.redeem_clue(&board);
+
+        self.save(game, &board, result).await
+    }
+
+    /// Reveals the answer that covers `position`.
+    pub async fn reveal_answer_at(
+        &self,
+        game_id: &GameId,
+        position: &Position,
+    ) -> Result<GameUpdate<Result<(), RevealFailure>>, GameServiceError> {
+        let (mut game, board) = self.load(game_id).await?;
+
+        let result = match board.answer_at(position) {
+            Some(answer) => game.reveal_answer(&board, &answer.id.clone()),
+            None => Err(RevealFailure::UnknownAnswer),
+        };
+
+        self.save(game, &board, result).await
+    }
+
+    pub async fn give_up(
+        &self,
+        game_id: &GameId,
+    ) -> Result<GameUpdate<Result<(), RevealFailure>>, GameServiceError> {
+        let (mut game, board) = self.load(game_id).await?;
+
+        let result = game.give_up(&board);
+
+        self.save(game, &board, result).await
+    }
+
+    /// Stops the game's clock until the player comes back to it.
+    pub async fn pause(&self, game_id: &GameId) -> Result<(), GameServiceError> {
+        let (mut game, board) = self.load(game_id).await?;
+        if game.is_paused() {
+            return Ok(());
+        }
+
+        game.pause();
+
+        self.save(game, &board, ()).await.map(|_| ())
+    }
+
+    pub async fn undo(
+        &self,
+        game_id: &GameId,
+    ) -> Result<GameUpdate<Result<(), UndoFailure>>, GameServiceError> {
+        let (mut game, board) = self.load(game_id).await?;
+
+        let result = game.undo();
+
+        self.save(game, &board, result).await
+    }
+
+    pub async fn restart(
+        &self,
+        game_id: &GameId,
+    ) -> Result<GameUpdate<Result<(), RestartFailure>>, GameServiceError> {
+        let (mut game, board) = self.load(game_id).await?;
+
+        let result = game.restart();
+
+        self.save(game, &board, result).await
+    }
+
+    async fn load(&self, game_id: &GameId) -> Result<(Game, Board), GameServiceError> {
+        let game = self
+            .game_repository
+            .by_id(game_id)
+            .await?
+            .ok_or(GameServiceError::GameNotFound)?;
+        let board = self
+            .board_provider
+            .by_id(&game.board_id)
+            .await?
+            .ok_or(GameServiceError::BoardNotFound)?;
+        Ok((game, board))
+    }
+
+    async fn save<T>(
+        &self,
+        game: Game,
+        board: &Board,
+        result: T,
+    ) -> Result<GameUpdate<T>, GameServiceError> {
+        let game = self
+            .game_repository
+            .update(game)
+            .await
+            .map_err(GameServiceError::CouldNotSave)?;
+        Ok(GameUpdate {
+            result,
+            view: ui::GameView::new(board, &game),
         })
     }
 }
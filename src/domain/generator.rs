@@ -0,0 +1,537 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use itertools::Itertools;
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    normalize_word, solver, Answer, AnswerId, AnswerType, Board, BoardId, ContiguousPositions,
+    GameRules, InvalidBoard, Position, PrefixDictionary, MIN_WORD_LENGTH,
+};
+
+/// What a puzzle author supplies: everything about the board except where the words go.
+#[derive(Clone, Debug)]
+pub struct BoardDraft {
+    pub id: BoardId,
+    pub editor: String,
+    pub print_date: NaiveDate,
+    pub theme: String,
+    pub spangram: String,
+    pub words: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GeneratorOptions {
+    pub width: usize,
+    pub height: usize,
+    /// The same seed always lays out the same draft the same way.
+    pub seed: u64,
+    /// How many complete layouts to compare when minimizing accidental bonus words.
+    pub layouts: usize,
+    /// How many times to start over before giving up on the draft.
+    pub max_attempts: u32,
+}
+
+impl Default for GeneratorOptions {
+    /// The size of the NYT board.
+    fn default() -> Self {
+        Self {
+            width: 6,
+            height: 8,
+            seed: 0,
+            layouts: 1,
+            max_attempts: 100,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GenerateError {
+    /// The words need a different number of tiles than the grid has.
+    WrongLetterCount {
+        letters: usize,
+        tiles: usize,
+    },
+    /// A word has something other than letters in it, such as a space or a hyphen.
+    NotLetters(String),
+    WordTooShort(String),
+    SpangramTooShort,
+    CouldNotPlaceWords,
+    InvalidBoard(Vec<InvalidBoard>),
+}
+
+/// How many steps one attempt may take before it's abandoned for a fresh shuffle.
+const STEPS_PER_ATTEMPT: u32 = 200_000;
+
+/// Lays the draft's words into a grid as paths that cover every tile without overlapping,
+/// with the spangram reaching across the board.
+///
+/// Given a dictionary, it compares `options.layouts` layouts and keeps the one with the
+/// fewest dictionary words traceable on it besides the theme words, so players aren't
+/// tripped up by accidental bonus words.
+pub fn generate(
+    draft: &BoardDraft,
+    options: &GeneratorOptions,
+    dictionary: Option<&dyn PrefixDictionary>,
+) -> Result<Board, GenerateError> {
+    let theme = draft.words.iter().chain([&draft.spangram]).collect_vec();
+    if let Some(word) = theme
+        .iter()
+        .find(|word| !word.chars().all(char::is_alphabetic))
+    {
+        return Err(GenerateError::NotLetters(word.to_string()));
+    }
+    if let Some(word) = theme
+        .iter()
+        .find(|word| word.chars().count() < MIN_WORD_LENGTH)
+    {
+        return Err(GenerateError::WordTooShort(word.to_string()));
+    }
+    let words = theme
+        .iter()
+        .map(|word| word.chars().map(tile_letter).collect_vec())
+        .collect_vec();
+    let letters = words.iter().map(Vec::len).sum();
+    let tiles = options.width * options.height;
+    if letters != tiles {
+        return Err(GenerateError::WrongLetterCount { letters, tiles });
+    }
+    if words.last().unwrap().len() < options.width.min(options.height) {
+        return Err(GenerateError::SpangramTooShort);
+    }
+
+    let theme_words: HashSet<String> = theme.iter().map(|word| normalize_word(word)).collect();
+    // A layout the board rejects is skipped, and only reported if no layout is valid.
+    let mut invalid = None;
+    let layouts = (0..options.max_attempts)
+        .filter_map(|attempt| {
+            let seed = options.seed.wrapping_add(attempt.into());
+            Layout::new(options.width, options.height, &words, seed).solve()
+        })
+        .filter_map(|paths| match to_board(draft, options, &words, &paths) {
+            Ok(board) => Some(board),
+            Err(error) => {
+                invalid.get_or_insert(error);
+                None
+            }
+        })
+        .take(options.layouts.max(1))
+        .collect_vec();
+
+    let best = match dictionary {
+        Some(dictionary) => layouts.into_iter().min_by_key(|board| {
+            solver::find_words(&board.tiles, dictionary, &GameRules::default())
+                .into_keys()
+                .filter(|word| !theme_words.contains(word))
+                .count()
+        }),
+        None => layouts.into_iter().next(),
+    };
+    best.ok_or(invalid.unwrap_or(GenerateError::CouldNotPlaceWords))
+}
+
+/// The tile a letter goes on. Each letter takes exactly one tile, so a letter whose capital
+/// is longer than itself, like `ß`, keeps its own form.
+fn tile_letter(letter: char) -> char {
+    let mut capital = letter.to_uppercase();
+    match (capital.next(), capital.next()) {
+        (Some(capital), None) => capital,
+        _ => letter,
+    }
+}
+
+fn to_board(
+    draft: &BoardDraft,
+    options: &GeneratorOptions,
+    words: &[Vec<char>],
+    paths: &[Vec<Position>],
+) -> Result<Board, GenerateError> {
+    let mut grid = vec![vec![' '; options.width]; options.height];
+    for (word, path) in words.iter().zip(paths) {
+        for (letter, position) in word.iter().zip(path) {
+            grid[position.row as usize][position.col as usize] = *letter;
+        }
+    }
+    let spangram = words.len() - 1;
+    let answers = draft
+        .words
+        .iter()
+        .chain([&draft.spangram])
+        .zip(paths)
+        .enumerate()
+        .map(|(index, (word, path))| {
+            let id: u32 = (index + 1).try_into().unwrap();
+            let answer_type = if index == spangram {
+                AnswerType::Spangram
+            } else {
+                AnswerType::Normal
+            };
+            let positions = ContiguousPositions::new(path.clone())
+                .expect("Generated paths should be contiguous");
+            Answer::new(AnswerId::new(id), answer_type, positions, word, id)
+        })
+        .collect();
+
+    Board::from_string(
+        draft.id.clone(),
+        draft.editor.clone(),
+        draft.theme.clone(),
+        draft.print_date,
+        answers,
+        &grid
+            .into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect_vec(),
+    )
+    .map_err(GenerateError::InvalidBoard)
+}
+
+/// One randomized backtracking attempt at placing the words.
+///
+/// The spangram goes down first, from one edge towards the opposite one. After that the
+/// first empty tile in reading order has to start some word, which keeps the search from
+/// leaving holes behind it. Whenever a word is placed, every pocket of empty tiles must
+/// still be fillable by some of the remaining words.
+struct Layout<'a> {
+    width: usize,
+    height: usize,
+    words: &'a [Vec<char>],
+    filled: Vec<bool>,
+    paths: Vec<Vec<Position>>,
+    rng: ChaCha8Rng,
+    steps_left: u32,
+}
+
+#[derive(Clone, Copy)]
+enum Axis {
+    Rows,
+    Cols,
+}
+
+impl<'a> Layout<'a> {
+    fn new(width: usize, height: usize, words: &'a [Vec<char>], seed: u64) -> Self {
+        Self {
+            width,
+            height,
+            words,
+            filled: vec![false; width * height],
+            paths: vec![vec![]; words.len()],
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            steps_left: STEPS_PER_ATTEMPT,
+        }
+    }
+
+    fn solve(mut self) -> Option<Vec<Vec<Position>>> {
+        let spangram = self.words.len() - 1;
+        let mut axes = vec![Axis::Rows, Axis::Cols];
+        axes.shuffle(&mut self.rng);
+        let mut remaining = (0..spangram).collect_vec();
+        for axis in axes {
+            let mut starts = self
+                .cells()
+                .filter(|&cell| self.coordinate(cell, axis) == 0)
+                .collect_vec();
+            starts.shuffle(&mut self.rng);
+            for start in starts {
+                let mut path = vec![start];
+                self.filled[start] = true;
+                let placed = self.trace(spangram, &mut path, Some(axis), &mut remaining);
+                self.filled[start] = false;
+                if placed {
+                    return Some(self.paths);
+                }
+            }
+        }
+        None
+    }
+
+    /// Places the remaining words, starting each at the first empty tile.
+    fn fill(&mut self, remaining: &mut Vec<usize>) -> bool {
+        let Some(start) = self.cells().find(|&cell| !self.filled[cell]) else {
+            return remaining.is_empty();
+        };
+        let mut order = remaining.clone();
+        order.shuffle(&mut self.rng);
+        for word in order.into_iter().unique_by(|&word| &self.words[word]) {
+            remaining.retain(|&other| other != word);
+            let mut path = vec![start];
+            self.filled[start] = true;
+            let placed = self.trace(word, &mut path, None, remaining);
+            self.filled[start] = false;
+            remaining.push(word);
+            if placed {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Extends `path` until it's as long as the word, then places the remaining words.
+    fn trace(
+        &mut self,
+        word: usize,
+        path: &mut Vec<usize>,
+        spans: Option<Axis>,
+        remaining: &mut Vec<usize>,
+    ) -> bool {
+        if self.steps_left == 0 {
+            return false;
+        }
+        self.steps_left -= 1;
+
+        let length = self.words[word].len();
+        let last = *path.last().unwrap();
+        if path.len() == length {
+            if spans.is_some_and(|axis| !self.reaches_far_edge(path, axis)) {
+                return false;
+            }
+            if !self.pockets_can_be_filled(remaining) || !self.fill(remaining) {
+                return false;
+            }
+            self.paths[word] = path.iter().map(|&cell| self.position(cell)).collect();
+            return true;
+        }
+
+        let mut next = self.neighbours(last);
+        next.shuffle(&mut self.rng);
+        for cell in next {
+            // The spangram can't wander so far that it no longer has the letters to get across.
+            if let Some(axis) = spans {
+                let far_edge = self.extent(axis) - 1;
+                let reached = path
+                    .iter()
+                    .chain([&cell])
+                    .any(|&cell| self.coordinate(cell, axis) == far_edge);
+                let steps_left = length - path.len() - 1;
+                if !reached && far_edge - self.coordinate(cell, axis) > steps_left {
+                    continue;
+                }
+            }
+            path.push(cell);
+            self.filled[cell] = true;
+            let placed = self.trace(word, path, spans, remaining);
+            self.filled[cell] = false;
+            path.pop();
+            if placed {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Whether each connected group of empty tiles is exactly as big as some of the
+    /// remaining words put together.
+    fn pockets_can_be_filled(&self, remaining: &[usize]) -> bool {
+        let mut sums = vec![false; self.filled.len() + 1];
+        sums[0] = true;
+        for &word in remaining {
+            let length = self.words[word].len();
+            for sum in (length..sums.len()).rev() {
+                sums[sum] |= sums[sum - length];
+            }
+        }
+
+        let mut seen = self.filled.clone();
+        for cell in self.cells() {
+            if seen[cell] {
+                continue;
+            }
+            seen[cell] = true;
+            let mut stack = vec![cell];
+            let mut size = 0;
+            while let Some(cell) = stack.pop() {
+                size += 1;
+                for next in self.neighbours(cell) {
+                    if !seen[next] {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+            if !sums[size] {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn reaches_far_edge(&self, path: &[usize], axis: Axis) -> bool {
+        let far_edge = self.extent(axis) - 1;
+        path.iter()
+            .any(|&cell| self.coordinate(cell, axis) == far_edge)
+    }
+
+    /// Empty tiles next to `cell`, diagonals included.
+    fn neighbours(&self, cell: usize) -> Vec<usize> {
+        let position = self.position(cell);
+        self.cells()
+            .filter(|&other| !self.filled[other])
+            .filter(|&other| self.position(other).is_adjacent_to(&position))
+            .collect()
+    }
+
+    fn cells(&self) -> std::ops::Range<usize> {
+        0..self.filled.len()
+    }
+
+    fn position(&self, cell: usize) -> Position {
+        Position::from_usize(cell / self.width, cell % self.width)
+    }
+
+    fn coordinate(&self, cell: usize, axis: Axis) -> usize {
+        match axis {
+            Axis::Rows => cell / self.width,
+            Axis::Cols => cell % self.width,
+        }
+    }
+
+    fn extent(&self, axis: Axis) -> usize {
+        match axis {
+            Axis::Rows => self.height,
+            Axis::Cols => self.width,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_fixtures::SortedDictionary;
+
+    fn draft() -> BoardDraft {
+        BoardDraft {
+            id: BoardId::new(1000),
+            editor: "Chris".to_string(),
+            print_date: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+            theme: "Big cats".to_string(),
+            spangram: "felines".to_string(),
+            words: [
+                "lion", "tiger", "puma", "jaguar", "ocelot", "lynx", "cheetah", "manul",
+            ]
+            .iter()
+            .map(|word| word.to_string())
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn lays_out_a_valid_board() {
+        let board = generate(&draft(), &GeneratorOptions::default(), None).unwrap();
+
+        assert_eq!(board.answers.len(), 9);
+        assert_eq!(board.spangram().word, "felines");
+        assert_eq!(board.tiles.dimensions().width, 6);
+        assert_eq!(board.tiles.dimensions().height, 8);
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_board() {
+        let options = GeneratorOptions {
+            seed: 7,
+            ..GeneratorOptions::default()
+        };
+
+        let first = generate(&draft(), &options, None).unwrap();
+        let second = generate(&draft(), &options, None).unwrap();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn words_must_fill_the_grid() {
+        let options = GeneratorOptions {
+            width: 5,
+            ..GeneratorOptions::default()
+        };
+
+        let generated = generate(&draft(), &options, None);
+
+        assert_eq!(
+            generated,
+            Err(GenerateError::WrongLetterCount {
+                letters: 48,
+                tiles: 40
+            })
+        );
+    }
+
+    #[test]
+    fn prefers_layouts_with_fewer_accidental_words() {
+        let dictionary = SortedDictionary::new(&[
+            "lint", "tail", "mail", "rain", "hate", "heat", "core", "loin", "gait",
+        ]);
+        let accidental_words = |board: &Board| {
+            solver::find_words(&board.tiles, &dictionary, &GameRules::default()).len()
+        };
+        let options = GeneratorOptions {
+            layouts: 10,
+            ..GeneratorOptions::default()
+        };
+
+        let best = generate(&draft(), &options, Some(&dictionary)).unwrap();
+
+        let each = (0..10)
+            .map(|seed| {
+                let options = GeneratorOptions {
+                    seed,
+                    ..GeneratorOptions::default()
+                };
+                generate(&draft(), &options, None).unwrap()
+            })
+            .collect_vec();
+        assert_eq!(
+            Some(&best),
+            each.iter().min_by_key(|board| accidental_words(board))
+        );
+        assert!(accidental_words(&best) < accidental_words(&each[0]));
+    }
+
+    #[test]
+    fn spangram_must_be_long_enough_to_be_a_word() {
+        let draft = BoardDraft {
+            spangram: "cat".to_string(),
+            ..draft()
+        };
+
+        let generated = generate(&draft, &GeneratorOptions::default(), None);
+
+        assert_eq!(
+            generated,
+            Err(GenerateError::WordTooShort("cat".to_string()))
+        );
+    }
+
+    #[test]
+    fn words_must_be_letters() {
+        let mut draft = draft();
+        draft.words[0] = "snow leopard".to_string();
+
+        let generated = generate(&draft, &GeneratorOptions::default(), None);
+
+        assert_eq!(
+            generated,
+            Err(GenerateError::NotLetters("snow leopard".to_string()))
+        );
+    }
+
+    #[test]
+    fn letters_are_counted_before_capitalizing() {
+        let mut draft = draft();
+        // Capitalizing would turn this into the six letter "GROSSE".
+        draft.words[7] = "große".to_string();
+
+        let board = generate(&draft, &GeneratorOptions::default(), None).unwrap();
+
+        assert_eq!(
+            board
+                .tiles
+                .0
+                .iter()
+                .flatten()
+                .filter(|tile| tile.0 == 'ß')
+                .count(),
+            1
+        );
+    }
+}
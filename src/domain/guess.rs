@@ -1,4 +1,10 @@
-use super::ContiguousPositions;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::{ContiguousPositions, GameRules};
+
+/// The shortest word a guess or an answer can spell under the NYT rules.
+pub const MIN_WORD_LENGTH: usize = 4;
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Guess {
@@ -6,15 +12,26 @@ pub struct Guess {
 }
 
 impl Guess {
-    pub fn new(positions: ContiguousPositions) -> Result<Self, CreateGuessError> {
-        if positions.len() < 4 {
+    pub fn new(
+        positions: ContiguousPositions,
+        rules: &GameRules,
+    ) -> Result<Self, CreateGuessError> {
+        if positions.len() < rules.min_word_length {
             return Err(CreateGuessError::TooShort);
         }
+        if positions
+            .iter()
+            .tuple_windows()
+            .any(|(first, second)| !rules.are_adjacent(first, second))
+        {
+            return Err(CreateGuessError::DiagonalStep);
+        }
         Ok(Self { positions })
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub enum CreateGuessError {
     TooShort,
+    DiagonalStep,
 }
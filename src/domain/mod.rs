@@ -1,36 +1,54 @@
 pub mod answer;
 pub mod board;
+pub mod board_provider;
 pub mod board_repository;
 pub mod contiguous_tiles;
+pub mod dictionary;
 pub mod game;
+pub mod game_event;
 pub mod game_repository;
+pub mod game_rules;
 pub mod game_service;
+pub mod generator;
 pub mod guess;
+pub mod share;
+pub mod solver;
 
 use std::char;
 use std::collections::HashSet;
 
+use serde::{Deserialize, Serialize};
+
 use crate::adapter::PositionDto;
 
 pub use self::answer::*;
 pub use self::board::*;
+pub use self::board_provider::*;
 pub use self::board_repository::*;
 pub use self::contiguous_tiles::*;
+pub use self::dictionary::*;
 pub use self::game::*;
+pub use self::game_event::*;
 pub use self::game_repository::*;
+pub use self::game_rules::*;
 pub use self::game_service::*;
 pub use self::guess::*;
+pub use self::share::*;
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct PlayerId(String);
 
 impl PlayerId {
     pub fn new(str: &str) -> Self {
         PlayerId(str.to_string())
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct Position {
     pub row: i32,
     pub col: i32,
@@ -49,6 +67,9 @@ impl Position {
     pub fn is_adjacent_to(&self, other: &Position) -> bool {
         (self.row - other.row).abs() <= 1 && (self.col - other.col).abs() <= 1
     }
+    pub fn is_orthogonally_adjacent_to(&self, other: &Position) -> bool {
+        (self.row - other.row).abs() + (self.col - other.col).abs() == 1
+    }
     pub fn left(self) -> Self {
         Self {
             row: self.row,
@@ -75,9 +96,14 @@ impl Position {
     }
 }
 
-impl From<PositionDto> for Position {
-    fn from(dto: PositionDto) -> Self {
-        Position::new(dto.0.try_into().unwrap(), dto.1.try_into().unwrap())
+impl TryFrom<PositionDto> for Position {
+    type Error = PositionDto;
+
+    fn try_from(dto: PositionDto) -> Result<Self, Self::Error> {
+        match (dto.0.try_into(), dto.1.try_into()) {
+            (Ok(row), Ok(col)) => Ok(Position::new(row, col)),
+            _ => Err(dto),
+        }
     }
 }
 
@@ -100,15 +126,19 @@ impl Tile {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub struct Clue {
     positions: ContiguousPositions,
+    /// Whether the second hint level has revealed the order the letters are spelled in.
+    #[serde(default)]
+    order_revealed: bool,
 }
 
 impl From<Answer> for Clue {
     fn from(value: Answer) -> Self {
         Self {
             positions: value.positions,
+            order_revealed: false,
         }
     }
 }
@@ -117,16 +147,21 @@ impl Clue {
     pub fn tiles_randomized(&self) -> HashSet<Position> {
         self.positions.inner_value().into_iter().collect()
     }
-}
 
-pub trait Dictionary {
-    fn contains_word(&self, word: &str) -> bool;
-}
+    /// The hinted word's tiles in spelling order, once that level of the hint is redeemed.
+    pub fn tiles_in_order(&self) -> Option<Vec<Position>> {
+        self.order_revealed.then(|| self.positions.inner_value())
+    }
 
-pub struct HashSetDictionary(HashSet<String>);
+    pub fn is_order_revealed(&self) -> bool {
+        self.order_revealed
+    }
+
+    pub fn reveal_order(&mut self) {
+        self.order_revealed = true;
+    }
 
-impl Dictionary for HashSetDictionary {
-    fn contains_word(&self, word: &str) -> bool {
-        self.0.contains(word)
+    pub fn is_for(&self, answer: &Answer) -> bool {
+        self.positions == answer.positions
     }
 }
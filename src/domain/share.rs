@@ -0,0 +1,63 @@
+use chrono::TimeDelta;
+use itertools::Itertools;
+
+use super::{Board, Game, GameAction, GameState};
+
+/// How many action symbols go on each line of the share grid.
+const SYMBOLS_PER_ROW: usize = 4;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ShareStyle {
+    Emoji,
+    /// For terminals and chat clients that can't show emoji.
+    Ascii,
+}
+
+impl GameAction {
+    fn share_symbol(&self, style: ShareStyle) -> &'static str {
+        match (style, self) {
+            (ShareStyle::Emoji, GameAction::NormalAnswerFound) => "🔵",
+            (ShareStyle::Emoji, GameAction::SpanogramFound) => "🟡",
+            (ShareStyle::Emoji, GameAction::Clue | GameAction::ClueOrder) => "💡",
+            (ShareStyle::Emoji, GameAction::AnswerRevealed) => "⚪",
+            (ShareStyle::Ascii, GameAction::NormalAnswerFound) => "o",
+            (ShareStyle::Ascii, GameAction::SpanogramFound) => "S",
+            (ShareStyle::Ascii, GameAction::Clue | GameAction::ClueOrder) => "?",
+            (ShareStyle::Ascii, GameAction::AnswerRevealed) => "x",
+        }
+    }
+}
+
+impl Game {
+    /// The result text players post after solving, in the same shape as the NYT's.
+    pub fn share_text(&self, board: &Board, style: ShareStyle) -> String {
+        let clue = match style {
+            ShareStyle::Emoji => format!("“{}”", board.clue),
+            ShareStyle::Ascii => format!("\"{}\"", board.clue),
+        };
+        let grid = self
+            .actions()
+            .chunks(SYMBOLS_PER_ROW)
+            .map(|row| row.iter().map(|action| action.share_symbol(style)).join(""))
+            .join("\n");
+        let time = match (self.state(), style) {
+            (GameState::Finished(summary), ShareStyle::Emoji) => {
+                format!("\n⏱️ {}", format_duration(summary.active_duration))
+            }
+            (GameState::Finished(summary), ShareStyle::Ascii) => {
+                format!("\nTime: {}", format_duration(summary.active_duration))
+            }
+            _ => String::new(),
+        };
+        format!("Strands #{}\n{}{}\n{}", board.id.0, clue, time, grid)
+    }
+}
+
+/// Formats a solve time as minutes and seconds, like `12:05`.
+pub fn format_duration(duration: TimeDelta) -> String {
+    format!(
+        "{}:{:02}",
+        duration.num_minutes(),
+        duration.num_seconds() % 60
+    )
+}
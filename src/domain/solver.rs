@@ -0,0 +1,448 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use itertools::Itertools;
+
+use super::{
+    normalize_word, AnswerType, Board, ContiguousPositions, GameRules, Position, PrefixDictionary,
+    Tiles,
+};
+
+/// Every dictionary word that can be traced on the tiles, with each path that spells it.
+///
+/// Paths are explored depth first from every tile and abandoned as soon as the letters so
+/// far don't begin any word, so only the part of the board that can spell something is
+/// ever visited.
+pub fn find_words(
+    tiles: &Tiles,
+    dictionary: &dyn PrefixDictionary,
+    rules: &GameRules,
+) -> BTreeMap<String, Vec<ContiguousPositions>> {
+    let mut search = Search {
+        tiles,
+        dictionary,
+        rules,
+        path: vec![],
+        word: String::new(),
+        found: BTreeMap::new(),
+    };
+    for position in tiles.all_positions() {
+        search.visit(position);
+    }
+    search.found
+}
+
+struct Search<'a> {
+    tiles: &'a Tiles,
+    dictionary: &'a dyn PrefixDictionary,
+    rules: &'a GameRules,
+    path: Vec<Position>,
+    word: String,
+    found: BTreeMap<String, Vec<ContiguousPositions>>,
+}
+
+impl Search<'_> {
+    fn visit(&mut self, position: Position) {
+        let Some(tile) = self.tiles.at_position(&position) else {
+            return;
+        };
+        let word_length = self.word.len();
+        self.word.push(tile.0);
+        self.path.push(position.clone());
+
+        if self.dictionary.has_prefix(&self.word) {
+            if self.path.len() >= self.rules.min_word_length
+                && self.dictionary.contains_word(&self.word)
+            {
+                self.found
+                    .entry(normalize_word(&self.word))
+                    .or_default()
+                    .push(ContiguousPositions::new(self.path.clone()).unwrap());
+            }
+            for next in self.neighbours(&position) {
+                self.visit(next);
+            }
+        }
+
+        self.path.pop();
+        self.word.truncate(word_length);
+    }
+
+    fn neighbours(&self, position: &Position) -> Vec<Position> {
+        (-1..=1)
+            .flat_map(|row| (-1..=1).map(move |col| (row, col)))
+            .map(|(row, col)| Position::new(position.row + row, position.col + col))
+            .filter(|next| {
+                self.rules.are_adjacent(position, next)
+                    && !self.path.contains(next)
+                    && self.tiles.at_position(next).is_some()
+            })
+            .collect()
+    }
+}
+
+/// A word placed on the board as part of a solution.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PlacedWord {
+    pub word: String,
+    pub answer_type: AnswerType,
+    pub positions: ContiguousPositions,
+}
+
+/// A way of splitting the whole board into words that don't share tiles, one of which is an
+/// edge to edge spangram.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Tiling(pub Vec<PlacedWord>);
+
+impl Tiling {
+    /// What a player would see of the solution. Tracing the same tiles in another order
+    /// spells out the same answer, so the order within a word doesn't matter.
+    fn answer_set(&self) -> BTreeSet<(bool, String, BTreeSet<Position>)> {
+        self.0
+            .iter()
+            .map(|placed| {
+                (
+                    placed.answer_type == AnswerType::Spangram,
+                    normalize_word(&placed.word),
+                    placed.positions.iter().cloned().collect(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AnswerUniqueness {
+    Unique,
+    /// The board can also be solved another way, like this one.
+    Ambiguous(Tiling),
+}
+
+/// Finds up to `limit` tilings of the board using the candidate words, such as those from
+/// `find_words`.
+///
+/// This is an exact cover problem: every tile and the spangram slot must be covered exactly
+/// once. It is searched with Knuth's Algorithm X, always branching on whichever tile has the
+/// fewest words left that could cover it. Tracings of a word over the same tiles count once.
+pub fn find_tilings(
+    tiles: &Tiles,
+    candidates: &BTreeMap<String, Vec<ContiguousPositions>>,
+    limit: usize,
+) -> Vec<Tiling> {
+    let dimensions = tiles.dimensions();
+    let positions = tiles.all_positions();
+    let spangram_column = positions.len();
+    let column_of = |position: &Position| positions.iter().position(|p| p == position);
+
+    let mut rows = vec![];
+    let mut columns = vec![];
+    for (word, paths) in candidates {
+        let paths = paths
+            .iter()
+            .unique_by(|path| path.iter().cloned().collect::<BTreeSet<_>>());
+        for path in paths {
+            let Some(covered) = path.iter().map(column_of).collect::<Option<Vec<_>>>() else {
+                continue;
+            };
+            let mut add = |answer_type: AnswerType, columns_covered: Vec<usize>| {
+                rows.push(PlacedWord {
+                    word: word.clone(),
+                    answer_type,
+                    positions: path.clone(),
+                });
+                columns.push(columns_covered);
+            };
+            if path.spans(&dimensions) {
+                add(
+                    AnswerType::Spangram,
+                    covered.iter().copied().chain([spangram_column]).collect(),
+                );
+            }
+            add(AnswerType::Normal, covered);
+        }
+    }
+
+    let mut search = ExactCover::new(columns, spangram_column + 1);
+    let mut solutions = vec![];
+    search.solve(&mut vec![], &mut solutions, limit);
+    solutions
+        .into_iter()
+        .map(|solution| Tiling(solution.into_iter().map(|row| rows[row].clone()).collect()))
+        .collect()
+}
+
+/// Checks whether the published answers are the only way to solve the board with words
+/// from the dictionary. The published answers always count, even if the dictionary
+/// doesn't know them.
+pub fn check_uniqueness(
+    board: &Board,
+    dictionary: &dyn PrefixDictionary,
+    rules: &GameRules,
+) -> AnswerUniqueness {
+    let mut candidates = find_words(&board.tiles, dictionary, rules);
+    for answer in &board.answers {
+        candidates
+            .entry(normalize_word(&answer.word))
+            .or_default()
+            .push(answer.positions.clone());
+    }
+    let published = Tiling(
+        board
+            .answers
+            .iter()
+            .map(|answer| PlacedWord {
+                word: answer.word.clone(),
+                answer_type: answer.answer_type.clone(),
+                positions: answer.positions.clone(),
+            })
+            .collect(),
+    )
+    .answer_set();
+
+    match find_tilings(&board.tiles, &candidates, 2)
+        .into_iter()
+        .find(|tiling| tiling.answer_set() != published)
+    {
+        Some(other) => AnswerUniqueness::Ambiguous(other),
+        None => AnswerUniqueness::Unique,
+    }
+}
+
+/// Algorithm X over rows that each cover a set of columns. Instead of unlinking nodes like
+/// dancing links does, each row keeps a count of its columns that are already covered, and
+/// is only a choice while that count is zero.
+struct ExactCover {
+    rows: Vec<Vec<usize>>,
+    rows_by_column: Vec<Vec<usize>>,
+    covered: Vec<bool>,
+    blocked: Vec<u32>,
+}
+
+impl ExactCover {
+    fn new(rows: Vec<Vec<usize>>, column_count: usize) -> Self {
+        let mut rows_by_column = vec![vec![]; column_count];
+        for (row, columns) in rows.iter().enumerate() {
+            for &column in columns {
+                rows_by_column[column].push(row);
+            }
+        }
+        Self {
+            blocked: vec![0; rows.len()],
+            covered: vec![false; column_count],
+            rows,
+            rows_by_column,
+        }
+    }
+
+    fn solve(&mut self, chosen: &mut Vec<usize>, solutions: &mut Vec<Vec<usize>>, limit: usize) {
+        if solutions.len() >= limit {
+            return;
+        }
+        let column = (0..self.covered.len())
+            .filter(|&column| !self.covered[column])
+            .min_by_key(|&column| self.choices(column).count());
+        let Some(column) = column else {
+            solutions.push(chosen.clone());
+            return;
+        };
+
+        for row in self.choices(column).collect_vec() {
+            self.set_chosen(row, true);
+            chosen.push(row);
+            self.solve(chosen, solutions, limit);
+            chosen.pop();
+            self.set_chosen(row, false);
+        }
+    }
+
+    fn choices(&self, column: usize) -> impl Iterator<Item = usize> + '_ {
+        self.rows_by_column[column]
+            .iter()
+            .copied()
+            .filter(|&row| self.blocked[row] == 0)
+    }
+
+    fn set_chosen(&mut self, row: usize, chosen: bool) {
+        for &column in &self.rows[row] {
+            self.covered[column] = chosen;
+            for &other in &self.rows_by_column[column] {
+                if chosen {
+                    self.blocked[other] += 1;
+                } else {
+                    self.blocked[other] -= 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::{
+        domain::{Answer, AnswerId, BoardId},
+        test_fixtures::SortedDictionary,
+    };
+
+    fn tiles() -> Tiles {
+        Tiles::from_strings(&["TEAS".to_string(), "ATES".to_string()])
+    }
+
+    #[test]
+    fn finds_every_path_of_every_word() {
+        let found = find_words(
+            &tiles(),
+            &SortedDictionary::new(&["teas", "seat", "eats", "tease", "zeta"]),
+            &GameRules::default(),
+        );
+
+        assert_eq!(
+            found.keys().collect::<Vec<_>>(),
+            vec!["seat", "teas", "tease"]
+        );
+        assert!(found["teas"].contains(
+            &ContiguousPositions::new(vec![
+                Position::new(0, 0),
+                Position::new(0, 1),
+                Position::new(0, 2),
+                Position::new(0, 3),
+            ])
+            .unwrap()
+        ));
+        assert!(found["teas"].len() > 1);
+        assert!(found.values().flatten().all(|path| path.len() >= 4));
+    }
+
+    #[test]
+    fn follows_the_rules_for_diagonals_and_length() {
+        let rules = GameRules {
+            min_word_length: 3,
+            allow_diagonals: false,
+            ..GameRules::default()
+        };
+
+        let found = find_words(
+            &tiles(),
+            &SortedDictionary::new(&["tea", "sat", "ta"]),
+            &rules,
+        );
+
+        assert_eq!(found.keys().collect::<Vec<_>>(), vec!["tea"]);
+        assert_eq!(found["tea"].len(), 3);
+    }
+
+    fn path(positions: &[(i32, i32)]) -> ContiguousPositions {
+        ContiguousPositions::new(
+            positions
+                .iter()
+                .map(|&(row, col)| Position::new(row, col))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    /// abcd
+    /// efgh
+    /// ijkl
+    fn published_board() -> Board {
+        let answer = |id, answer_type, word, positions: &[(i32, i32)]| {
+            Answer::new(AnswerId::new(id), answer_type, path(positions), word, id)
+        };
+        Board::from_string(
+            BoardId::new(1),
+            "Chris".to_string(),
+            "Try This".to_string(),
+            NaiveDate::from_ymd_opt(2024, 4, 25).unwrap(),
+            vec![
+                answer(
+                    1,
+                    AnswerType::Spangram,
+                    "abcd",
+                    &[(0, 0), (0, 1), (0, 2), (0, 3)],
+                ),
+                answer(
+                    2,
+                    AnswerType::Normal,
+                    "efji",
+                    &[(1, 0), (1, 1), (2, 1), (2, 0)],
+                ),
+                answer(
+                    3,
+                    AnswerType::Normal,
+                    "ghlk",
+                    &[(1, 2), (1, 3), (2, 3), (2, 2)],
+                ),
+            ],
+            &["abcd".to_string(), "efgh".to_string(), "ijkl".to_string()],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn published_answers_can_be_the_only_solution() {
+        let board = published_board();
+
+        let uniqueness = check_uniqueness(
+            &board,
+            &SortedDictionary::new(&["efgh"]),
+            &GameRules::default(),
+        );
+
+        assert_eq!(uniqueness, AnswerUniqueness::Unique);
+    }
+
+    #[test]
+    fn finds_another_way_to_tile_the_board() {
+        let board = published_board();
+        let dictionary = SortedDictionary::new(&["abfe", "cdhg", "ijkl"]);
+
+        let uniqueness = check_uniqueness(&board, &dictionary, &GameRules::default());
+
+        let AnswerUniqueness::Ambiguous(other) = uniqueness else {
+            panic!("Expected another solution, got {:?}", uniqueness);
+        };
+        assert_eq!(
+            other
+                .0
+                .iter()
+                .map(|placed| (placed.word.as_str(), placed.answer_type.clone()))
+                .sorted()
+                .collect_vec(),
+            vec![
+                ("abfe", AnswerType::Normal),
+                ("cdhg", AnswerType::Normal),
+                ("ijkl", AnswerType::Spangram),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_tiling_has_one_spangram_and_covers_the_board() {
+        let board = published_board();
+        let candidates = find_words(
+            &board.tiles,
+            &SortedDictionary::new(&["abcd", "efgh", "ijkl", "abfe", "cdhg", "efji", "ghlk"]),
+            &GameRules::default(),
+        );
+
+        let tilings = find_tilings(&board.tiles, &candidates, usize::MAX);
+
+        assert!(tilings.len() > 2);
+        for tiling in tilings {
+            let spangrams = tiling
+                .0
+                .iter()
+                .filter(|placed| placed.answer_type == AnswerType::Spangram)
+                .count();
+            let covered = tiling
+                .0
+                .iter()
+                .flat_map(|placed| placed.positions.iter().cloned())
+                .sorted()
+                .collect_vec();
+            assert_eq!(spangrams, 1);
+            assert_eq!(covered, board.tiles.all_positions());
+        }
+    }
+}
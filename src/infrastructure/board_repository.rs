@@ -1,28 +1,338 @@
 use crate::domain::*;
 use async_trait::async_trait;
 use chrono::NaiveDate;
-use std::{collections::HashMap, sync::Mutex};
+use itertools::Itertools;
+use rusqlite::{params, Connection, OptionalExtension, ToSql};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use tokio::fs;
 
+use super::{data_dir, json_column, SqliteDatabase};
+
+#[derive(Default)]
 pub struct InMemoryBoardRepository(Mutex<HashMap<BoardId, Board>>);
 
 #[async_trait]
 impl BoardRepository for InMemoryBoardRepository {
-    async fn by_id(&self, id: &BoardId) -> Option<Board> {
-        self.0.lock().map(|m| m.get(id).cloned()).unwrap()
+    async fn by_id(&self, id: &BoardId) -> Result<Option<Board>, StorageError> {
+        Ok(self.0.lock().map(|m| m.get(id).cloned()).unwrap())
     }
 
-    async fn by_date(&self, date: &NaiveDate) -> Option<Board> {
+    async fn by_date(&self, date: &NaiveDate) -> Result<Option<Board>, StorageError> {
         // cheeky way to find the "first" element. Fix at some point
-        self.0
+        Ok(self
+            .0
             .lock()
             .map(|m| m.values().find(|board| &board.print_date == date).cloned())
-            .unwrap()
+            .unwrap())
     }
 
-    async fn insert(&self, board: Board) -> () {
+    async fn insert(&self, board: Board) -> Result<(), StorageError> {
         self.0
             .lock()
             .map(|mut m| m.insert(board.id.clone(), board))
             .unwrap();
+        Ok(())
+    }
+}
+
+/// Stores each board as `<id>.json` next to an `index.json` mapping print dates to board ids.
+pub struct FileBoardRepository {
+    root: PathBuf,
+    index_lock: tokio::sync::Mutex<()>,
+}
+
+impl FileBoardRepository {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            index_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Uses `$XDG_DATA_HOME/strands/boards`, falling back to `~/.local/share/strands/boards`.
+    pub fn in_data_dir() -> Option<Self> {
+        data_dir().map(|dir| Self::new(dir.join("strands").join("boards")))
+    }
+
+    pub async fn dates(&self) -> Result<Vec<NaiveDate>, StorageError> {
+        Ok(self.read_index().await?.into_keys().collect())
+    }
+
+    fn board_path(&self, id: &BoardId) -> PathBuf {
+        self.root.join(format!("{}.json", id.0))
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.root.join("index.json")
+    }
+
+    async fn read_index(&self) -> Result<BTreeMap<NaiveDate, BoardId>, StorageError> {
+        Ok(read_json(&self.index_path()).await?.unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl BoardRepository for FileBoardRepository {
+    async fn by_id(&self, id: &BoardId) -> Result<Option<Board>, StorageError> {
+        read_json(&self.board_path(id)).await
+    }
+
+    async fn by_date(&self, date: &NaiveDate) -> Result<Option<Board>, StorageError> {
+        match self.read_index().await?.remove(date) {
+            Some(id) => self.by_id(&id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn insert(&self, board: Board) -> Result<(), StorageError> {
+        let _guard = self.index_lock.lock().await;
+        fs::create_dir_all(&self.root).await?;
+
+        write_json(&self.board_path(&board.id), &board).await?;
+        let mut index = self.read_index().await?;
+        index.insert(board.print_date, board.id);
+        write_json(&self.index_path(), &index).await
+    }
+}
+
+async fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Option<T>, StorageError> {
+    let contents = match fs::read(path).await {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    serde_json::from_slice(&contents)
+        .map(Some)
+        .map_err(|error| StorageError::Unavailable(format!("{}: {}", path.display(), error)))
+}
+
+async fn write_json<T: serde::Serialize>(path: &Path, value: &T) -> Result<(), StorageError> {
+    let contents = serde_json::to_vec_pretty(value).expect("Boards should always serialize");
+    // Write then rename so a crash mid-write never leaves a truncated file behind.
+    let temporary_path = path.with_extension("json.tmp");
+    fs::write(&temporary_path, contents).await?;
+    fs::rename(&temporary_path, path).await?;
+    Ok(())
+}
+
+impl From<io::Error> for StorageError {
+    fn from(error: io::Error) -> Self {
+        StorageError::Unavailable(error.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(error: rusqlite::Error) -> Self {
+        StorageError::Unavailable(error.to_string())
+    }
+}
+
+pub struct SqliteBoardRepository(SqliteDatabase);
+
+impl SqliteBoardRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        Self(database)
+    }
+
+    fn find(&self, clause: &str, param: &dyn ToSql) -> Result<Option<Board>, StorageError> {
+        find_board(&self.0.connection(), clause, param)
+    }
+}
+
+/// Loads the first board matching `clause`, such as `id = ?1`, along with its answers.
+pub(crate) fn find_board(
+    connection: &Connection,
+    clause: &str,
+    param: &dyn ToSql,
+) -> Result<Option<Board>, StorageError> {
+    let Some((id, print_date, editor, clue, tiles)) = connection
+        .query_row(
+            &format!(
+                "SELECT id, print_date, editor, clue, tiles FROM boards WHERE {} LIMIT 1",
+                clause
+            ),
+            [param],
+            |row| {
+                Ok((
+                    row.get::<_, u32>(0)?,
+                    row.get::<_, NaiveDate>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            },
+        )
+        .optional()?
+    else {
+        return Ok(None);
+    };
+
+    let answers = connection
+        .prepare(
+            "SELECT id, answer_type, word, display_order, positions FROM answers
+             WHERE board_id = ?1 ORDER BY id",
+        )
+        .and_then(|mut statement| {
+            statement
+                .query_map([id], |row| {
+                    Ok(Answer {
+                        id: AnswerId::new(row.get(0)?),
+                        answer_type: match row.get::<_, String>(1)?.as_str() {
+                            "Spangram" => AnswerType::Spangram,
+                            _ => AnswerType::Normal,
+                        },
+                        word: row.get(2)?,
+                        order: row.get(3)?,
+                        positions: json_column(row, 4)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<Answer>>>()
+        })?;
+    let rows = tiles.lines().map(|row| row.to_string()).collect_vec();
+
+    Board::from_string(BoardId::new(id), editor, clue, print_date, answers, &rows)
+        .map(Some)
+        .map_err(|violations| StorageError::InvalidBoard(BoardId::new(id), violations))
+}
+
+#[async_trait]
+impl BoardRepository for SqliteBoardRepository {
+    async fn by_id(&self, id: &BoardId) -> Result<Option<Board>, StorageError> {
+        self.find("id = ?1", &id.0)
+    }
+
+    async fn by_date(&self, date: &NaiveDate) -> Result<Option<Board>, StorageError> {
+        self.find("print_date = ?1", date)
+    }
+
+    async fn insert(&self, board: Board) -> Result<(), StorageError> {
+        let mut connection = self.0.connection();
+        let transaction = connection.transaction()?;
+        let tiles = board
+            .tiles
+            .0
+            .iter()
+            .map(|row| row.iter().map(|tile| tile.0).collect::<String>())
+            .join("\n");
+        transaction.execute(
+            "INSERT OR REPLACE INTO boards (id, print_date, editor, clue, tiles)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                board.id.0,
+                board.print_date,
+                board.editor,
+                board.clue,
+                tiles
+            ],
+        )?;
+        transaction.execute("DELETE FROM answers WHERE board_id = ?1", [board.id.0])?;
+        for answer in &board.answers {
+            let answer_type = match answer.answer_type {
+                AnswerType::Normal => "Normal",
+                AnswerType::Spangram => "Spangram",
+            };
+            transaction.execute(
+                "INSERT INTO answers (board_id, id, answer_type, word, display_order, positions)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    board.id.0,
+                    answer.id.0,
+                    answer_type,
+                    answer.word,
+                    answer.order,
+                    serde_json::to_string(&answer.positions).unwrap()
+                ],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_fixtures::board_on;
+
+    fn temporary_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("strands-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        root
+    }
+
+    #[tokio::test]
+    async fn boards_survive_a_new_repository() {
+        let root = temporary_root("survive");
+        let date = NaiveDate::from_ymd_opt(2024, 4, 24).unwrap();
+        let board = board_on(42, date);
+
+        FileBoardRepository::new(&root)
+            .insert(board.clone())
+            .await
+            .unwrap();
+        let reopened = FileBoardRepository::new(&root);
+
+        assert_eq!(reopened.by_date(&date).await, Ok(Some(board.clone())));
+        assert_eq!(reopened.by_id(&BoardId::new(42)).await, Ok(Some(board)));
+        assert_eq!(reopened.dates().await, Ok(vec![date]));
+    }
+
+    #[tokio::test]
+    async fn unwritable_directory_is_reported() {
+        // A file where the board directory should be can't be written into.
+        let root = temporary_root("unwritable");
+        std::fs::write(&root, "").unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 4, 24).unwrap();
+
+        let inserted = FileBoardRepository::new(&root)
+            .insert(board_on(42, date))
+            .await;
+
+        assert!(matches!(inserted, Err(StorageError::Unavailable(_))));
+        std::fs::remove_file(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn sqlite_round_trips_boards() {
+        let repository = SqliteBoardRepository::new(SqliteDatabase::open_in_memory().unwrap());
+        let date = NaiveDate::from_ymd_opt(2024, 4, 24).unwrap();
+        let board = board_on(42, date);
+
+        repository.insert(board.clone()).await.unwrap();
+
+        assert_eq!(repository.by_date(&date).await, Ok(Some(board.clone())));
+        assert_eq!(repository.by_id(&BoardId::new(42)).await, Ok(Some(board)));
+    }
+
+    #[tokio::test]
+    async fn invalid_stored_board_is_reported() {
+        let repository = SqliteBoardRepository::new(SqliteDatabase::open_in_memory().unwrap());
+        let date = NaiveDate::from_ymd_opt(2024, 4, 24).unwrap();
+        repository.insert(board_on(42, date)).await.unwrap();
+        repository
+            .0
+            .connection()
+            .execute("DELETE FROM answers WHERE board_id = 42", [])
+            .unwrap();
+
+        let board = repository.by_id(&BoardId::new(42)).await;
+
+        assert!(matches!(board, Err(StorageError::InvalidBoard(id, _)) if id == BoardId::new(42)));
+    }
+
+    #[tokio::test]
+    async fn missing_board_is_none() {
+        let repository = FileBoardRepository::new(temporary_root("missing"));
+
+        let board = repository
+            .by_date(&NaiveDate::from_ymd_opt(2024, 4, 24).unwrap())
+            .await;
+
+        assert_eq!(board, Ok(None))
     }
 }
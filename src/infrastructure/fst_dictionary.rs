@@ -0,0 +1,161 @@
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+use fst::{Set, SetBuilder};
+use itertools::Itertools;
+use memmap2::Mmap;
+
+use crate::domain::*;
+
+use super::data_dir;
+
+/// A dictionary compiled into a finite-state transducer, which answers both exact and
+/// prefix lookups by walking the automaton one letter at a time.
+///
+/// Compiled files are memory mapped rather than read, so even large word lists open
+/// instantly.
+pub struct FstDictionary(Set<Bytes>);
+
+enum Bytes {
+    Owned(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Bytes::Owned(bytes) => bytes,
+            Bytes::Mapped(mmap) => mmap,
+        }
+    }
+}
+
+impl FstDictionary {
+    pub fn from_words<I>(words: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let words = words
+            .into_iter()
+            .map(|word| normalize_word(word.as_ref()))
+            .filter(|word| !word.is_empty())
+            .sorted()
+            .dedup();
+        let set = Set::from_iter(words).expect("Sorted words should build a set");
+        Self(Set::new(Bytes::Owned(set.into_fst().into_inner())).unwrap())
+    }
+
+    /// Writes the words of `dictionary` to `path` in the format `open` reads.
+    pub fn compile(dictionary: &HashSetDictionary, path: impl AsRef<Path>) -> io::Result<usize> {
+        let path = path.as_ref();
+        // Write then rename, as a game may have the old file mapped.
+        let temporary_path = path.with_extension("fst.tmp");
+        let mut builder = SetBuilder::new(io::BufWriter::new(File::create(&temporary_path)?))
+            .map_err(io::Error::other)?;
+        let words = dictionary.words().sorted().collect_vec();
+        builder.extend_iter(&words).map_err(io::Error::other)?;
+        builder.finish().map_err(io::Error::other)?;
+        fs::rename(&temporary_path, path)?;
+        Ok(words.len())
+    }
+
+    /// Where compiled word lists go unless told otherwise:
+    /// `$XDG_DATA_HOME/strands/words.fst`, falling back to `~/.local/share/strands/words.fst`.
+    pub fn default_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("strands").join("words.fst"))
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // Safety: `compile` renames a new file over the old one rather than writing into it,
+        // so the mapped bytes stay intact even if the list is recompiled while we run.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Set::new(Bytes::Mapped(mmap))
+            .map(Self)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Dictionary for FstDictionary {
+    fn contains_word(&self, word: &str) -> bool {
+        self.0.contains(normalize_word(word))
+    }
+}
+
+impl PrefixDictionary for FstDictionary {
+    fn has_prefix(&self, prefix: &str) -> bool {
+        let fst = self.0.as_fst();
+        let mut node = fst.root();
+        for byte in normalize_word(prefix).bytes() {
+            match node.find_input(byte) {
+                Some(index) => node = fst.node(node.transition(index).addr),
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn answers_word_and_prefix_lookups() {
+        let dictionary = FstDictionary::from_words(["Lion", "lioness", "tiger"]);
+
+        assert!(dictionary.contains_word("LION"));
+        assert!(!dictionary.contains_word("lio"));
+        assert!(dictionary.has_prefix("lio"));
+        assert!(dictionary.has_prefix("TIG"));
+        assert!(dictionary.has_prefix(""));
+        assert!(!dictionary.has_prefix("tio"));
+    }
+
+    #[test]
+    fn compiles_to_a_file_that_can_be_mapped() {
+        let path = std::env::temp_dir().join(format!("strands-words-{}.fst", std::process::id()));
+        let words = HashSetDictionary::default()
+            .with_min_length(4)
+            .allow(["cat", "lion", "tiger", "puma"])
+            .block(["puma"]);
+
+        let compiled = FstDictionary::compile(&words, &path).unwrap();
+        let dictionary = FstDictionary::open(&path).unwrap();
+
+        assert_eq!(compiled, 2);
+        assert_eq!(dictionary.len(), 2);
+        assert!(dictionary.contains_word("tiger"));
+        assert!(!dictionary.contains_word("puma"));
+        assert!(dictionary.has_prefix("li"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn recompiling_leaves_open_dictionaries_intact() {
+        let path =
+            std::env::temp_dir().join(format!("strands-recompile-{}.fst", std::process::id()));
+        FstDictionary::compile(&HashSetDictionary::default().allow(["lion"]), &path).unwrap();
+        let before = FstDictionary::open(&path).unwrap();
+
+        FstDictionary::compile(&HashSetDictionary::default().allow(["tiger"]), &path).unwrap();
+        let after = FstDictionary::open(&path).unwrap();
+
+        assert!(before.contains_word("lion"));
+        assert!(after.contains_word("tiger"));
+        assert!(!after.contains_word("lion"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
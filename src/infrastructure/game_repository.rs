@@ -1,18 +1,490 @@
 use crate::domain::*;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use chrono::NaiveDate;
+use itertools::Itertools;
+use rusqlite::{params, Connection, OptionalExtension, ToSql, Transaction};
+use std::{collections::HashMap, sync::Mutex};
 
-pub struct InMemoryGameRepository(HashMap<GameId, Game>);
+use super::{json_column, SqliteDatabase};
+
+#[derive(Default)]
+pub struct InMemoryGameRepository(Mutex<HashMap<GameId, Game>>);
 
 #[async_trait]
 impl GameRepository for InMemoryGameRepository {
-    async fn by_id(&self, id: &GameId) -> Option<Game> {
-        self.0.get(id).cloned()
-    }
-    async fn by_player_and_board(&self, player_id: &PlayerId, board_id: &BoardId) -> Option<Game> {
-        self.0
-            .values()
-            .find(|game| &game.player_id == player_id && &game.board_id == board_id)
-            .cloned()
+    async fn by_id(&self, id: &GameId) -> Result<Option<Game>, StorageError> {
+        Ok(self.0.lock().map(|m| m.get(id).cloned()).unwrap())
+    }
+    async fn by_player_and_board(
+        &self,
+        player_id: &PlayerId,
+        board_id: &BoardId,
+    ) -> Result<Option<Game>, StorageError> {
+        Ok(self
+            .0
+            .lock()
+            .map(|m| {
+                m.values()
+                    .find(|game| &game.player_id == player_id && &game.board_id == board_id)
+                    .cloned()
+            })
+            .unwrap())
+    }
+    async fn list_by_player(&self, player_id: &PlayerId) -> Result<Vec<Game>, StorageError> {
+        Ok(self
+            .0
+            .lock()
+            .map(|m| {
+                m.values()
+                    .filter(|game| &game.player_id == player_id)
+                    .cloned()
+                    .sorted_by(|a, b| a.board_id.cmp(&b.board_id))
+                    .collect()
+            })
+            .unwrap())
+    }
+    async fn list_by_board(&self, board_id: &BoardId) -> Result<Vec<Game>, StorageError> {
+        Ok(self
+            .0
+            .lock()
+            .map(|m| {
+                m.values()
+                    .filter(|game| &game.board_id == board_id)
+                    .cloned()
+                    .sorted_by(|a, b| a.player_id.cmp(&b.player_id))
+                    .collect()
+            })
+            .unwrap())
+    }
+    async fn insert(&self, game: Game) -> Result<(), SaveGameError> {
+        let mut games = self.0.lock().unwrap();
+        if games.contains_key(&game.id) {
+            return Err(SaveGameError::AlreadyExists);
+        }
+        games.insert(game.id.clone(), game);
+        Ok(())
+    }
+    async fn update(&self, mut game: Game) -> Result<Game, SaveGameError> {
+        let mut games = self.0.lock().unwrap();
+        let stored = games.get(&game.id).ok_or(SaveGameError::NotFound)?;
+        if stored.version != game.version {
+            return Err(SaveGameError::VersionConflict {
+                expected: game.version,
+                actual: stored.version,
+            });
+        }
+        game.version += 1;
+        games.insert(game.id.clone(), game.clone());
+        Ok(game)
+    }
+}
+
+pub struct SqliteGameRepository(SqliteDatabase);
+
+impl SqliteGameRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        Self(database)
+    }
+
+    fn find_all(&self, clause: &str, params: &[&dyn ToSql]) -> Result<Vec<Game>, StorageError> {
+        let connection = self.0.connection();
+        let games = connection
+            .prepare(&format!(
+                "SELECT id, version, board_id FROM games WHERE {}",
+                clause
+            ))
+            .and_then(|mut statement| {
+                statement
+                    .query_map(params, |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, u32>(1)?,
+                            BoardId::new(row.get(2)?),
+                        ))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })?;
+
+        games
+            .into_iter()
+            .map(|(id, version, board_id)| {
+                let mut game = Game::from_events(board_id, events(&connection, &id)?)
+                    .map_err(|error| StorageError::InvalidGame(GameId::new(&id), error))?;
+                game.version = version;
+                Ok(game)
+            })
+            .collect()
+    }
+}
+
+/// How many players took on the puzzle printed on one day, and how many of them finished it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DailyStats {
+    pub print_date: NaiveDate,
+    pub players: u32,
+    pub finished: u32,
+}
+
+impl SqliteGameRepository {
+    /// Stats for every day between `from` and `to` inclusive that somebody played.
+    pub fn daily_stats(
+        &self,
+        from: &NaiveDate,
+        to: &NaiveDate,
+    ) -> Result<Vec<DailyStats>, StorageError> {
+        let stats = self
+            .0
+            .connection()
+            .prepare(
+                "SELECT boards.print_date, COUNT(*), SUM(EXISTS (
+                     SELECT 1 FROM events
+                     WHERE events.game_id = games.id
+                       AND json_extract(events.event, '$.kind') = 'Finished'
+                 ))
+                 FROM games JOIN boards ON boards.id = games.board_id
+                 WHERE boards.print_date BETWEEN ?1 AND ?2
+                 GROUP BY boards.print_date
+                 ORDER BY boards.print_date",
+            )
+            .and_then(|mut statement| {
+                statement
+                    .query_map(params![from, to], |row| {
+                        Ok(DailyStats {
+                            print_date: row.get(0)?,
+                            players: row.get(1)?,
+                            finished: row.get(2)?,
+                        })
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })?;
+        Ok(stats)
+    }
+}
+
+/// Reads a game's events in the order they were recorded.
+fn events(connection: &Connection, game_id: &str) -> rusqlite::Result<Vec<GameEvent>> {
+    connection
+        .prepare("SELECT event FROM events WHERE game_id = ?1 ORDER BY sequence")
+        .and_then(|mut statement| {
+            statement
+                .query_map([game_id], |row| json_column(row, 0))?
+                .collect::<rusqlite::Result<Vec<GameEvent>>>()
+        })
+}
+
+impl From<rusqlite::Error> for SaveGameError {
+    fn from(error: rusqlite::Error) -> Self {
+        SaveGameError::Storage(error.into())
+    }
+}
+
+#[async_trait]
+impl GameRepository for SqliteGameRepository {
+    async fn by_id(&self, id: &GameId) -> Result<Option<Game>, StorageError> {
+        Ok(self.find_all("id = ?1", &[&id.as_str()])?.pop())
+    }
+
+    async fn by_player_and_board(
+        &self,
+        player_id: &PlayerId,
+        board_id: &BoardId,
+    ) -> Result<Option<Game>, StorageError> {
+        Ok(self
+            .find_all(
+                "player_id = ?1 AND board_id = ?2 LIMIT 1",
+                &[&player_id.as_str(), &board_id.0],
+            )?
+            .pop())
+    }
+
+    async fn list_by_player(&self, player_id: &PlayerId) -> Result<Vec<Game>, StorageError> {
+        self.find_all("player_id = ?1 ORDER BY board_id", &[&player_id.as_str()])
+    }
+
+    async fn list_by_board(&self, board_id: &BoardId) -> Result<Vec<Game>, StorageError> {
+        self.find_all("board_id = ?1 ORDER BY player_id", &[&board_id.0])
+    }
+
+    async fn insert(&self, game: Game) -> Result<(), SaveGameError> {
+        let mut connection = self.0.connection();
+        let transaction = connection.transaction()?;
+        let inserted = transaction.execute(
+            "INSERT INTO games (id, version, board_id, player_id)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (id) DO NOTHING",
+            params![
+                game.id.as_str(),
+                game.version,
+                game.board_id.0,
+                game.player_id.as_str(),
+            ],
+        )?;
+        if inserted == 0 {
+            return Err(SaveGameError::AlreadyExists);
+        }
+
+        write_events(&transaction, &game)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    async fn update(&self, mut game: Game) -> Result<Game, SaveGameError> {
+        let mut connection = self.0.connection();
+        let transaction = connection.transaction()?;
+        let updated = transaction.execute(
+            "UPDATE games SET version = version + 1 WHERE id = ?1 AND version = ?2",
+            params![game.id.as_str(), game.version],
+        )?;
+        if updated == 0 {
+            let actual: Option<u32> = transaction
+                .query_row(
+                    "SELECT version FROM games WHERE id = ?1",
+                    [game.id.as_str()],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            return Err(match actual {
+                Some(actual) => SaveGameError::VersionConflict {
+                    expected: game.version,
+                    actual,
+                },
+                None => SaveGameError::NotFound,
+            });
+        }
+
+        write_events(&transaction, &game)?;
+        transaction.commit()?;
+        game.version += 1;
+        Ok(game)
+    }
+}
+
+fn write_events(transaction: &Transaction, game: &Game) -> rusqlite::Result<()> {
+    let game_id = game.id.as_str();
+    transaction.execute("DELETE FROM events WHERE game_id = ?1", [game_id])?;
+    for (sequence, event) in game.events().iter().enumerate() {
+        transaction.execute(
+            "INSERT INTO events (game_id, sequence, event) VALUES (?1, ?2, ?3)",
+            params![game_id, sequence, serde_json::to_string(event).unwrap()],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::{infrastructure::SqliteBoardRepository, test_fixtures::board_on};
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_713_945_600 + seconds, 0).unwrap()
+    }
+
+    fn played_game() -> Game {
+        let answer = |id, answer_type, row, word| {
+            Answer::new(
+                AnswerId::new(id),
+                answer_type,
+                ContiguousPositions::new((0..4).map(|col| Position::new(row, col)).collect())
+                    .unwrap(),
+                word,
+                id,
+            )
+        };
+        let guess = |seconds, answer: Answer| GameEvent {
+            at: at(seconds),
+            kind: GameEventKind::GuessSubmitted {
+                positions: answer.positions.clone(),
+                outcome: GuessOutcome::FoundAnswer(answer),
+            },
+        };
+        Game::from_events(
+            BoardId::new(42),
+            vec![
+                GameEvent {
+                    at: at(0),
+                    kind: GameEventKind::Created {
+                        player_id: PlayerId::new("chrismcdonnell"),
+                        rules: GameRules {
+                            hint_cost: 5,
+                            max_hints: Some(2),
+                            ..GameRules::default()
+                        },
+                    },
+                },
+                guess(30, answer(3, AnswerType::Normal, 1, "abcd")),
+                GameEvent {
+                    at: at(45),
+                    kind: GameEventKind::GuessSubmitted {
+                        positions: ContiguousPositions::new(
+                            (0..4).map(|col| Position::new(2, col)).collect(),
+                        )
+                        .unwrap(),
+                        outcome: GuessOutcome::BonusWord("hell".to_string()),
+                    },
+                },
+                guess(60, answer(1, AnswerType::Spangram, 0, "efgh")),
+                GameEvent {
+                    at: at(60),
+                    kind: GameEventKind::Finished,
+                },
+            ],
+        )
+        .unwrap()
+    }
+
+    fn repositories() -> Vec<Box<dyn GameRepository>> {
+        vec![
+            Box::new(InMemoryGameRepository::default()),
+            Box::new(SqliteGameRepository::new(
+                SqliteDatabase::open_in_memory().unwrap(),
+            )),
+        ]
+    }
+
+    #[tokio::test]
+    async fn round_trips_games() {
+        for repository in repositories() {
+            let game = played_game();
+
+            repository.insert(game.clone()).await.unwrap();
+
+            assert_eq!(repository.by_id(&game.id).await, Ok(Some(game.clone())));
+            assert_eq!(
+                repository
+                    .by_player_and_board(&game.player_id, &game.board_id)
+                    .await,
+                Ok(Some(game))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn cannot_insert_game_twice() {
+        for repository in repositories() {
+            repository.insert(played_game()).await.unwrap();
+
+            let duplicate = repository.insert(played_game()).await;
+
+            assert_eq!(duplicate, Err(SaveGameError::AlreadyExists));
+        }
+    }
+
+    #[tokio::test]
+    async fn update_replaces_history_and_bumps_version() {
+        for repository in repositories() {
+            let mut game = played_game();
+            repository.insert(game.clone()).await.unwrap();
+
+            game = Game::from_events(game.board_id.clone(), game.events()[..2].to_vec()).unwrap();
+            let updated = repository.update(game.clone()).await.unwrap();
+
+            assert_eq!(updated.version, 1);
+            assert_eq!(repository.by_id(&game.id).await, Ok(Some(updated)));
+        }
+    }
+
+    #[tokio::test]
+    async fn stale_update_is_rejected() {
+        for repository in repositories() {
+            let game = played_game();
+            repository.insert(game.clone()).await.unwrap();
+            let _ = repository.update(game.clone()).await.unwrap();
+
+            let stale = repository.update(game).await;
+
+            assert_eq!(
+                stale,
+                Err(SaveGameError::VersionConflict {
+                    expected: 0,
+                    actual: 1
+                })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn update_requires_existing_game() {
+        for repository in repositories() {
+            let missing = repository.update(played_game()).await;
+
+            assert_eq!(missing, Err(SaveGameError::NotFound));
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_games_by_player_and_board() {
+        for repository in repositories() {
+            let first = Game::new(
+                BoardId::new(1),
+                PlayerId::new("alice"),
+                GameRules::default(),
+            );
+            let second = Game::new(
+                BoardId::new(2),
+                PlayerId::new("alice"),
+                GameRules::default(),
+            );
+            let other_player =
+                Game::new(BoardId::new(1), PlayerId::new("bob"), GameRules::default());
+            for game in [&second, &other_player, &first] {
+                repository.insert(game.clone()).await.unwrap();
+            }
+
+            let by_player = repository.list_by_player(&PlayerId::new("alice")).await;
+            let by_board = repository.list_by_board(&BoardId::new(1)).await;
+
+            assert_eq!(by_player, Ok(vec![first.clone(), second]));
+            assert_eq!(by_board, Ok(vec![first, other_player]));
+        }
+    }
+
+    #[tokio::test]
+    async fn unreplayable_game_is_reported() {
+        let repository = SqliteGameRepository::new(SqliteDatabase::open_in_memory().unwrap());
+        let game = played_game();
+        repository.insert(game.clone()).await.unwrap();
+        repository
+            .0
+            .connection()
+            .execute("DELETE FROM events WHERE sequence = 0", [])
+            .unwrap();
+
+        let loaded = repository.by_id(&game.id).await;
+
+        assert_eq!(
+            loaded,
+            Err(StorageError::InvalidGame(
+                game.id,
+                ReplayError::NotCreatedFirst
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn counts_players_and_finishes_by_date() {
+        let database = SqliteDatabase::open_in_memory().unwrap();
+        let boards = SqliteBoardRepository::new(database.clone());
+        let games = SqliteGameRepository::new(database);
+        let date = NaiveDate::from_ymd_opt(2024, 4, 24).unwrap();
+        let next_day = date.succ_opt().unwrap();
+        boards.insert(board_on(42, date)).await.unwrap();
+        boards.insert(board_on(43, next_day)).await.unwrap();
+        games.insert(played_game()).await.unwrap();
+        let unfinished = Game::new(BoardId::new(42), PlayerId::new("bob"), GameRules::default());
+        games.insert(unfinished).await.unwrap();
+
+        let stats = games.daily_stats(&date, &next_day);
+
+        assert_eq!(
+            stats,
+            Ok(vec![DailyStats {
+                print_date: date,
+                players: 2,
+                finished: 1
+            }])
+        );
     }
 }
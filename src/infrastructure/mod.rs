@@ -1,7 +1,23 @@
 pub mod board_repository;
+pub mod fst_dictionary;
 pub mod game_repository;
 pub mod nyt_client;
+pub mod sqlite;
+pub mod word_file;
 
 pub use self::board_repository::*;
+pub use self::fst_dictionary::*;
 pub use self::game_repository::*;
 pub use self::nyt_client::*;
+pub use self::sqlite::*;
+pub use self::word_file::*;
+
+use std::{env, path::PathBuf};
+
+/// Resolves `$XDG_DATA_HOME`, falling back to `~/.local/share`.
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+}
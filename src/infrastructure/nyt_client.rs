@@ -1,22 +1,140 @@
-use crate::adapter::{NYTBoardDto, NytClient};
+use crate::adapter::{NYTBoardDto, NytClient, NytClientError};
 use async_trait::async_trait;
 use chrono::prelude::*;
+use reqwest::StatusCode;
+use std::{future::Future, time::Duration};
 
-pub struct HttpNytClient;
+pub struct HttpNytClient {
+    client: reqwest::Client,
+    retries: u32,
+    backoff: Duration,
+}
 
-#[async_trait]
-impl NytClient for HttpNytClient {
-    async fn by_date(&self, date: &NaiveDate) -> NYTBoardDto {
+impl HttpNytClient {
+    /// Each request gives up after `timeout`; transient failures are retried up to `retries`
+    /// times, waiting `backoff` before the first retry and doubling it after each one.
+    pub fn new(timeout: Duration, retries: u32, backoff: Duration) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .expect("Should be able to build an HTTP client"),
+            retries,
+            backoff,
+        }
+    }
+
+    async fn fetch(&self, date: &NaiveDate) -> Result<NYTBoardDto, NytClientError> {
         let url = format!(
             "https://www.nytimes.com/games-assets/strands/{}.json",
             date.format("%Y-%m-%d")
         );
-        let http_response = reqwest::get(url)
-            .await
-            .unwrap()
-            .json::<NYTBoardDto>()
-            .await
-            .unwrap();
-        http_response
+        let response = self.client.get(url).send().await.map_err(request_error)?;
+        match response.status() {
+            StatusCode::NOT_FOUND => return Err(NytClientError::NotFound(*date)),
+            status if !status.is_success() => return Err(NytClientError::Status(status.as_u16())),
+            _ => {}
+        }
+        let body = response.bytes().await.map_err(request_error)?;
+        serde_json::from_slice(&body).map_err(|error| NytClientError::Schema(error.to_string()))
+    }
+}
+
+impl Default for HttpNytClient {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(10), 3, Duration::from_millis(500))
+    }
+}
+
+#[async_trait]
+impl NytClient for HttpNytClient {
+    async fn by_date(&self, date: &NaiveDate) -> Result<NYTBoardDto, NytClientError> {
+        with_retries(self.retries, self.backoff, || self.fetch(date)).await
+    }
+}
+
+fn request_error(error: reqwest::Error) -> NytClientError {
+    if error.is_timeout() {
+        NytClientError::Timeout
+    } else {
+        NytClientError::Network(error.to_string())
+    }
+}
+
+async fn with_retries<T, F, Fut>(
+    retries: u32,
+    mut backoff: Duration,
+    mut request: F,
+) -> Result<T, NytClientError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, NytClientError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Err(error) if error.is_transient() && attempt < retries => {
+                attempt += 1;
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+
+    #[tokio::test]
+    async fn retries_transient_failures() {
+        let attempts = Cell::new(0);
+
+        let result = with_retries(3, Duration::ZERO, || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move {
+                match attempt {
+                    1 => Err(NytClientError::Timeout),
+                    2 => Err(NytClientError::Status(503)),
+                    _ => Ok(attempt),
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result, Ok(3))
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_retries_are_exhausted() {
+        let attempts = Cell::new(0);
+
+        let result: Result<(), _> = with_retries(2, Duration::ZERO, || {
+            attempts.set(attempts.get() + 1);
+            async { Err(NytClientError::Timeout) }
+        })
+        .await;
+
+        assert_eq!((result, attempts.get()), (Err(NytClientError::Timeout), 3))
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_missing_puzzle() {
+        let attempts = Cell::new(0);
+        let date = NaiveDate::from_ymd_opt(2099, 1, 1).unwrap();
+
+        let result: Result<(), _> = with_retries(3, Duration::ZERO, || {
+            attempts.set(attempts.get() + 1);
+            async move { Err(NytClientError::NotFound(date)) }
+        })
+        .await;
+
+        assert_eq!(
+            (result, attempts.get()),
+            (Err(NytClientError::NotFound(date)), 1)
+        )
     }
 }
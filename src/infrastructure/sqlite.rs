@@ -0,0 +1,387 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, types::Type, Connection, Row, Transaction};
+use serde::de::DeserializeOwned;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::domain::*;
+
+use super::board_repository::find_board;
+
+/// Each entry is applied once, in order, and recorded in `PRAGMA user_version`.
+const MIGRATIONS: &[Migration] = &[
+    Migration::Sql(
+        r#"
+    CREATE TABLE boards (
+        id INTEGER PRIMARY KEY,
+        print_date TEXT NOT NULL,
+        editor TEXT NOT NULL,
+        clue TEXT NOT NULL,
+        tiles TEXT NOT NULL
+    );
+    CREATE INDEX boards_print_date ON boards (print_date);
+
+    CREATE TABLE answers (
+        board_id INTEGER NOT NULL REFERENCES boards (id) ON DELETE CASCADE,
+        id INTEGER NOT NULL,
+        answer_type TEXT NOT NULL,
+        word TEXT NOT NULL,
+        display_order INTEGER NOT NULL,
+        positions TEXT NOT NULL,
+        PRIMARY KEY (board_id, id)
+    );
+
+    CREATE TABLE games (
+        id TEXT PRIMARY KEY,
+        board_id INTEGER NOT NULL,
+        player_id TEXT NOT NULL,
+        clue_progress_counter INTEGER NOT NULL,
+        active_clue TEXT
+    );
+    CREATE INDEX games_player_board ON games (player_id, board_id);
+
+    CREATE TABLE guesses (
+        game_id TEXT NOT NULL REFERENCES games (id) ON DELETE CASCADE,
+        sequence INTEGER NOT NULL,
+        positions TEXT NOT NULL,
+        PRIMARY KEY (game_id, sequence)
+    );
+
+    CREATE TABLE found_answers (
+        game_id TEXT NOT NULL REFERENCES games (id) ON DELETE CASCADE,
+        sequence INTEGER NOT NULL,
+        answer_id INTEGER NOT NULL,
+        PRIMARY KEY (game_id, sequence)
+    );
+
+    CREATE TABLE actions (
+        game_id TEXT NOT NULL REFERENCES games (id) ON DELETE CASCADE,
+        sequence INTEGER NOT NULL,
+        action TEXT NOT NULL,
+        PRIMARY KEY (game_id, sequence)
+    );
+"#,
+    ),
+    Migration::Sql(
+        r#"
+    ALTER TABLE games ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+    CREATE INDEX games_board ON games (board_id);
+"#,
+    ),
+    Migration::Sql(
+        r#"
+    ALTER TABLE games ADD COLUMN started_at TEXT;
+    ALTER TABLE games ADD COLUMN finished_at TEXT;
+"#,
+    ),
+    Migration::Sql(
+        r#"
+    CREATE TABLE bonus_words (
+        game_id TEXT NOT NULL REFERENCES games (id) ON DELETE CASCADE,
+        sequence INTEGER NOT NULL,
+        word TEXT NOT NULL,
+        PRIMARY KEY (game_id, sequence)
+    );
+"#,
+    ),
+    Migration::Sql(
+        r#"
+    -- Games saved before rules were configurable were played under the defaults.
+    ALTER TABLE games ADD COLUMN rules TEXT NOT NULL DEFAULT '{}';
+"#,
+    ),
+    Migration::Rust(events_from_legacy_history),
+];
+
+enum Migration {
+    Sql(&'static str),
+    /// For changes SQL can't express, such as replaying a game against its board.
+    Rust(fn(&Transaction) -> rusqlite::Result<()>),
+}
+
+impl Migration {
+    fn apply(&self, transaction: &Transaction) -> rusqlite::Result<()> {
+        match self {
+            Migration::Sql(sql) => transaction.execute_batch(sql),
+            Migration::Rust(migrate) => migrate(transaction),
+        }
+    }
+}
+
+/// Moves games saved before events were recorded onto an event log. Each stored guess is
+/// replayed against the game's board, and each hint is placed just before the answer found
+/// after it, so the rebuilt game ends with the same answers, bonus words and hints. Guesses
+/// that were rejected no longer affect a game and are left behind. Only the start and
+/// finish were timed, so every event but `Finished` is stamped with the start time.
+fn events_from_legacy_history(transaction: &Transaction) -> rusqlite::Result<()> {
+    transaction.execute_batch(
+        r#"
+    CREATE TABLE events (
+        game_id TEXT NOT NULL REFERENCES games (id) ON DELETE CASCADE,
+        sequence INTEGER NOT NULL,
+        event TEXT NOT NULL,
+        PRIMARY KEY (game_id, sequence)
+    );
+"#,
+    )?;
+
+    let games = transaction
+        .prepare("SELECT id, board_id, player_id, rules, started_at, finished_at FROM games")?
+        .query_map([], |row| {
+            Ok(LegacyGame {
+                id: row.get(0)?,
+                board_id: row.get(1)?,
+                player_id: PlayerId::new(&row.get::<_, String>(2)?),
+                rules: json_column(row, 3)?,
+                started_at: row.get(4)?,
+                finished_at: row.get(5)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<LegacyGame>>>()?;
+    for game in games {
+        let unplayable = |reason: String| {
+            rusqlite::Error::FromSqlConversionFailure(
+                1,
+                Type::Integer,
+                format!(
+                    "Game {} was played on board {}, which {}, so it can't be replayed",
+                    game.id, game.board_id, reason
+                )
+                .into(),
+            )
+        };
+        let board = find_board(transaction, "id = ?1", &game.board_id)
+            .map_err(|error| unplayable(format!("could not be loaded ({})", error)))?
+            .ok_or_else(|| unplayable("isn't stored".to_string()))?;
+        for (sequence, event) in game.events(transaction, &board)?.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO events (game_id, sequence, event) VALUES (?1, ?2, ?3)",
+                params![game.id, sequence, serde_json::to_string(event).unwrap()],
+            )?;
+        }
+    }
+
+    transaction.execute_batch(
+        r#"
+    DROP TABLE guesses;
+    DROP TABLE found_answers;
+    DROP TABLE actions;
+    DROP TABLE bonus_words;
+    ALTER TABLE games DROP COLUMN clue_progress_counter;
+    ALTER TABLE games DROP COLUMN active_clue;
+    ALTER TABLE games DROP COLUMN started_at;
+    ALTER TABLE games DROP COLUMN finished_at;
+    ALTER TABLE games DROP COLUMN rules;
+"#,
+    )
+}
+
+struct LegacyGame {
+    id: String,
+    board_id: u32,
+    player_id: PlayerId,
+    rules: GameRules,
+    started_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+impl LegacyGame {
+    fn events(&self, connection: &Connection, board: &Board) -> rusqlite::Result<Vec<GameEvent>> {
+        let guesses: Vec<ContiguousPositions> = self.history(connection, "guesses", "positions")?;
+        let mut actions = self
+            .history::<GameAction>(connection, "actions", "action")?
+            .into_iter()
+            .peekable();
+        let mut bonus_words = self
+            .history::<String>(connection, "bonus_words", "json_quote(word)")?
+            .into_iter()
+            .peekable();
+        let at = self.started_at.unwrap_or_else(Utc::now);
+        let event = |kind| GameEvent { at, kind };
+
+        let mut events = vec![event(GameEventKind::Created {
+            player_id: self.player_id.clone(),
+            rules: self.rules.clone(),
+        })];
+        let mut found_answer_ids = vec![];
+        for positions in guesses {
+            let outcome = match board.guess_is_answer(&Guess {
+                positions: positions.clone(),
+            }) {
+                FoundAnswer::Found(answer) if !found_answer_ids.contains(&answer.id) => {
+                    while let Some(hint) = actions.next_if(GameAction::is_hint) {
+                        events.extend(hint_event(&hint, board, &found_answer_ids).map(event));
+                    }
+                    actions.next();
+                    found_answer_ids.push(answer.id.clone());
+                    GuessOutcome::FoundAnswer(answer)
+                }
+                FoundAnswer::Found(_) => continue,
+                FoundAnswer::NotAnswer => {
+                    let word = board.get_word(&positions).map(|word| word.to_lowercase());
+                    match bonus_words.next_if(|bonus_word| Some(bonus_word) == word.as_ref()) {
+                        Some(word) => GuessOutcome::BonusWord(word),
+                        None => continue,
+                    }
+                }
+            };
+            events.push(event(GameEventKind::GuessSubmitted { positions, outcome }));
+        }
+        for hint in actions {
+            events.extend(hint_event(&hint, board, &found_answer_ids).map(event));
+        }
+        if let Some(finished_at) = self.finished_at {
+            events.push(GameEvent {
+                at: finished_at,
+                kind: GameEventKind::Finished,
+            });
+        }
+        Ok(events)
+    }
+
+    /// Reads one column of a per-game history table, in the order it was saved.
+    fn history<T: DeserializeOwned>(
+        &self,
+        connection: &Connection,
+        table: &str,
+        column: &str,
+    ) -> rusqlite::Result<Vec<T>> {
+        connection
+            .prepare(&format!(
+                "SELECT {} FROM {} WHERE game_id = ?1 ORDER BY sequence",
+                column, table
+            ))?
+            .query_map([&self.id], |row| json_column(row, 0))?
+            .collect()
+    }
+}
+
+/// The event a legacy hint action stands for, given the answers found before it.
+fn hint_event(
+    action: &GameAction,
+    board: &Board,
+    found_answer_ids: &[AnswerId],
+) -> Option<GameEventKind> {
+    match action {
+        GameAction::Clue => board
+            .get_next_clue(found_answer_ids)
+            .map(GameEventKind::HintRedeemed),
+        GameAction::ClueOrder => Some(GameEventKind::HintOrderRevealed),
+        _ => None,
+    }
+}
+
+/// A migrated SQLite connection shared by the board and game repositories.
+#[derive(Clone)]
+pub struct SqliteDatabase(Arc<Mutex<Connection>>);
+
+impl SqliteDatabase {
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        Self::migrated(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::migrated(Connection::open_in_memory()?)
+    }
+
+    fn migrated(mut connection: Connection) -> rusqlite::Result<Self> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+        let version: usize =
+            connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let transaction = connection.transaction()?;
+            migration.apply(&transaction)?;
+            transaction.pragma_update(None, "user_version", index + 1)?;
+            transaction.commit()?;
+        }
+        Ok(Self(Arc::new(Mutex::new(connection))))
+    }
+
+    pub(crate) fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.0.lock().unwrap()
+    }
+}
+
+/// Reads a column holding a JSON encoded value, such as a list of positions.
+pub(crate) fn json_column<T: DeserializeOwned>(row: &Row, index: usize) -> rusqlite::Result<T> {
+    let json: String = row.get(index)?;
+    serde_json::from_str(&json)
+        .map_err(|error| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, error.into()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::infrastructure::{SqliteBoardRepository, SqliteGameRepository};
+
+    use super::*;
+
+    /// A connection at the last version before events, holding `rows` of legacy history.
+    fn legacy_connection(rows: &str) -> Connection {
+        let mut connection = Connection::open_in_memory().unwrap();
+        let legacy = MIGRATIONS.len() - 1;
+        let transaction = connection.transaction().unwrap();
+        for migration in &MIGRATIONS[..legacy] {
+            migration.apply(&transaction).unwrap();
+        }
+        transaction
+            .pragma_update(None, "user_version", legacy)
+            .unwrap();
+        transaction.execute_batch(rows).unwrap();
+        transaction.commit().unwrap();
+        connection
+    }
+
+    #[tokio::test]
+    async fn carries_legacy_history_into_events() {
+        let connection = legacy_connection(
+            r#"
+                INSERT INTO boards VALUES (7, '2024-04-24', 'Chris', 'Try This', 'ab' || char(10) || 'dc');
+                INSERT INTO answers VALUES
+                    (7, 1, 'Spangram', 'abcd', 1, '[{"row":0,"col":0},{"row":0,"col":1},{"row":1,"col":1},{"row":1,"col":0}]');
+                INSERT INTO games (id, board_id, player_id, clue_progress_counter, started_at, finished_at, rules)
+                    VALUES ('chris-7', 7, 'chris', 0, '2024-04-24 08:00:00+00:00', '2024-04-24 08:05:00+00:00', '{"hint_cost":1}');
+                INSERT INTO guesses VALUES
+                    ('chris-7', 0, '[{"row":0,"col":0},{"row":0,"col":1},{"row":1,"col":0},{"row":1,"col":1}]'),
+                    ('chris-7', 1, '[{"row":0,"col":0},{"row":1,"col":1},{"row":0,"col":1},{"row":1,"col":0}]'),
+                    ('chris-7', 2, '[{"row":0,"col":0},{"row":0,"col":1},{"row":1,"col":1},{"row":1,"col":0}]');
+                INSERT INTO found_answers VALUES ('chris-7', 0, 1);
+                INSERT INTO actions VALUES ('chris-7', 0, '"Clue"'), ('chris-7', 1, '"SpanogramFound"');
+                INSERT INTO bonus_words VALUES ('chris-7', 0, 'abdc');
+                "#,
+        );
+
+        let database = SqliteDatabase::migrated(connection).unwrap();
+        let game = SqliteGameRepository::new(database.clone())
+            .by_id(&GameId::new("chris-7"))
+            .await
+            .unwrap()
+            .unwrap();
+        let board = SqliteBoardRepository::new(database)
+            .by_id(&BoardId::new(7))
+            .await;
+
+        assert!(matches!(board, Ok(Some(_))));
+        assert_eq!(game.player_id, PlayerId::new("chris"));
+        assert_eq!(game.found_answer_ids(), vec![AnswerId::new(1)]);
+        assert_eq!(game.bonus_words(), vec!["abdc".to_string()]);
+        assert_eq!(
+            game.actions(),
+            vec![GameAction::Clue, GameAction::SpanogramFound]
+        );
+        assert_eq!(game.guesses().len(), 2);
+        assert_eq!(game.clue_progress_counter(), 0);
+        assert!(game.is_finished());
+    }
+
+    #[test]
+    fn refuses_to_carry_over_games_without_their_board() {
+        let connection = legacy_connection(
+            "INSERT INTO games (id, board_id, player_id, clue_progress_counter)
+             VALUES ('chris-7', 7, 'chris', 0);",
+        );
+
+        assert!(SqliteDatabase::migrated(connection).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use flate2::read::GzDecoder;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Reads a word list with one word per line, skipping blank lines and `#` comments.
+/// Gzipped files are recognized by their header rather than their extension.
+pub fn read_word_file(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let is_gzip = reader.fill_buf()?.starts_with(&GZIP_MAGIC);
+    if is_gzip {
+        read_words(BufReader::new(GzDecoder::new(reader)))
+    } else {
+        read_words(reader)
+    }
+}
+
+fn read_words(reader: impl BufRead) -> io::Result<Vec<String>> {
+    reader
+        .lines()
+        .filter_map(|line| match line {
+            Ok(line) => {
+                let word = line.trim();
+                (!word.is_empty() && !word.starts_with('#')).then(|| Ok(word.to_string()))
+            }
+            Err(error) => Some(Err(error)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    #[test]
+    fn reads_plain_and_gzipped_files() {
+        let dir = std::env::temp_dir().join(format!("strands-words-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let contents = "# animals\nlion\n\n  tiger \r\n";
+        let plain = dir.join("words.txt");
+        std::fs::write(&plain, contents).unwrap();
+        let gzipped = dir.join("words.txt.gz");
+        let mut encoder = GzEncoder::new(File::create(&gzipped).unwrap(), Compression::default());
+        encoder.write_all(contents.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let expected = vec!["lion".to_string(), "tiger".to_string()];
+        assert_eq!(read_word_file(&plain).unwrap(), expected);
+        assert_eq!(read_word_file(&gzipped).unwrap(), expected);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -3,95 +3,614 @@ pub mod domain;
 pub mod infrastructure;
 pub mod ui;
 
-use adapter::{NYTBoardDto, NytClient};
+use adapter::NytClient;
 use chrono::prelude::*;
+use clap::{Args, Parser, Subcommand};
 use crossterm::{
     event::{self, Event, KeyCode, KeyEvent},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
-use infrastructure::HttpNytClient;
-use ratatui::prelude::*;
-use std::io::{self, stdout};
-use ui::Board;
+use domain::{
+    AnswerType, BoardProvider, BoardRepository, ContiguousPositions, CreateGuessError, Dictionary,
+    GameRepository, GameRules, GameService, GameServiceError, GameUpdate, Guess, GuessFailure,
+    GuessSuccess, HashSetDictionary, PlayerId, RedeemClueFailure, RestartFailure, RevealFailure,
+    ShareStyle, UndoFailure, MIN_WORD_LENGTH,
+};
+use infrastructure::{
+    read_word_file, FileBoardRepository, FstDictionary, HttpNytClient, InMemoryBoardRepository,
+    InMemoryGameRepository, SqliteBoardRepository, SqliteDatabase, SqliteGameRepository,
+};
+use ratatui::{prelude::*, widgets::Paragraph};
+use std::{
+    env,
+    ffi::OsString,
+    io::{self, stdout},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Arc,
+    time::Duration,
+};
+use ui::{copy_to_clipboard, GameView, SelectTileError, Selection};
+
+#[derive(Parser)]
+#[command(about = "Play the NYT Strands puzzle in your terminal")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    rules: RulesArgs,
+    /// Seconds to wait for the NYT before giving up on a request
+    #[arg(long, global = true, default_value_t = 10)]
+    timeout: u64,
+    /// How many times to retry a request that failed for a transient reason
+    #[arg(long, global = true, default_value_t = 3)]
+    retries: u32,
+    /// Milliseconds to wait before the first retry, doubling after each one
+    #[arg(long, global = true, default_value_t = 500)]
+    backoff: u64,
+}
+
+/// House rules for new games. Games already in progress keep the rules they started with.
+#[derive(Args)]
+struct RulesArgs {
+    /// The fewest letters a guess can have
+    #[arg(long, default_value_t = MIN_WORD_LENGTH)]
+    min_word_length: usize,
+    /// How many non-theme words each hint costs
+    #[arg(long, default_value_t = GameRules::default().hint_cost)]
+    hint_cost: u32,
+    /// The most hints a player can redeem
+    #[arg(long)]
+    max_hints: Option<u32>,
+    /// Only allow steps up, down, left and right
+    #[arg(long)]
+    no_diagonals: bool,
+    /// Let tiles of found words be used again
+    #[arg(long)]
+    reuse_tiles: bool,
+    /// Count any path of enough letters towards hints, even if it's not in the word list
+    #[arg(long)]
+    any_bonus_word: bool,
+    /// Play an official round, with undo and restarting turned off
+    #[arg(long)]
+    official: bool,
+}
+
+impl RulesArgs {
+    fn rules(&self) -> GameRules {
+        GameRules {
+            min_word_length: self.min_word_length,
+            hint_cost: self.hint_cost,
+            allow_diagonals: !self.no_diagonals,
+            found_tiles_lock: !self.reuse_tiles,
+            max_hints: self.max_hints,
+            bonus_words_need_dictionary: !self.any_bonus_word,
+            allow_undo: !self.official,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Download every puzzle in a date range into the local archive
+    Fetch {
+        #[arg(long)]
+        from: NaiveDate,
+        #[arg(long)]
+        to: NaiveDate,
+        /// How many puzzles to download at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+    },
+    /// Show how many players took on and finished each day's puzzle
+    Stats {
+        #[arg(long)]
+        from: NaiveDate,
+        #[arg(long)]
+        to: NaiveDate,
+    },
+    /// Compile the word lists into a file that loads instantly on later runs
+    CompileWords {
+        /// Where to write the compiled words, by default the data directory
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
 
 #[tokio::main]
-async fn main() -> io::Result<()> {
+async fn main() -> io::Result<ExitCode> {
+    let cli = Cli::parse();
+    let (game_repository, board_repository) = repositories()?;
+    let nyt_client = Arc::new(HttpNytClient::new(
+        Duration::from_secs(cli.timeout),
+        cli.retries,
+        Duration::from_millis(cli.backoff),
+    ));
+
+    match cli.command {
+        None => {
+            play(
+                game_repository,
+                board_repository,
+                nyt_client,
+                cli.rules.rules(),
+            )
+            .await
+        }
+        Some(Command::Fetch {
+            from,
+            to,
+            concurrency,
+        }) => {
+            let board_provider = BoardProvider::new(board_repository, nyt_client);
+            Ok(fetch(&board_provider, from, to, concurrency).await)
+        }
+        Some(Command::Stats { from, to }) => stats(from, to),
+        Some(Command::CompileWords { output }) => compile_words(output),
+    }
+}
+
+async fn play(
+    game_repository: Arc<dyn GameRepository>,
+    board_repository: Arc<dyn BoardRepository>,
+    nyt_client: Arc<dyn NytClient>,
+    rules: GameRules,
+) -> io::Result<ExitCode> {
     let date = Local::now();
-    let nyt_client = HttpNytClient;
-    let http_response = nyt_client.by_date(&date.date_naive()).await;
+    let game_service =
+        GameService::new(game_repository, board_repository, nyt_client, dictionary()?)
+            .with_rules(rules);
+    let view = match game_service
+        .start_or_resume(&date.date_naive(), &PlayerId::new("local"))
+        .await
+    {
+        Ok(view) => view,
+        Err(error) => {
+            eprintln!("Could not start today's game: {}", error);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
 
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-    let domain_board: domain::Board = http_response.into();
-    let board: ui::Board = domain_board.into();
-    let mut app = App::new(board);
-    app.run(&mut terminal)?;
+    let mut app = App::new(game_service, view);
+    let paused = app.run(&mut terminal).await?;
 
     disable_raw_mode()?;
     stdout().execute(LeaveAlternateScreen)?;
-    Ok(())
+    if let Err(error) = paused {
+        eprintln!("Could not pause the game: {}", error);
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn fetch(
+    board_provider: &BoardProvider,
+    from: NaiveDate,
+    to: NaiveDate,
+    concurrency: usize,
+) -> ExitCode {
+    let summary = board_provider.fetch_range(from, to, concurrency).await;
+
+    println!(
+        "Fetched {} puzzles, skipped {} already stored, {} failed",
+        summary.fetched.len(),
+        summary.skipped.len(),
+        summary.failed.len()
+    );
+    for (date, error) in &summary.failed {
+        println!("  {}: {}", date, error);
+    }
+
+    if summary.failed.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn stats(from: NaiveDate, to: NaiveDate) -> io::Result<ExitCode> {
+    let Some(path) = env::var_os("STRANDS_DATABASE") else {
+        eprintln!("Stats need the SQLite database, set STRANDS_DATABASE");
+        return Ok(ExitCode::FAILURE);
+    };
+    let database = SqliteDatabase::open(path).map_err(io::Error::other)?;
+    let days = match SqliteGameRepository::new(database).daily_stats(&from, &to) {
+        Ok(days) => days,
+        Err(error) => {
+            eprintln!("Could not read stats: {}", error);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    for day in days {
+        println!(
+            "{}: {} played, {} finished",
+            day.print_date, day.players, day.finished
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Uses the SQLite database at `STRANDS_DATABASE` when set, otherwise boards are cached as
+/// files in the data directory and games only live for the session.
+fn repositories() -> io::Result<(Arc<dyn GameRepository>, Arc<dyn BoardRepository>)> {
+    if let Some(path) = env::var_os("STRANDS_DATABASE") {
+        let database = SqliteDatabase::open(path).map_err(io::Error::other)?;
+        return Ok((
+            Arc::new(SqliteGameRepository::new(database.clone())),
+            Arc::new(SqliteBoardRepository::new(database)),
+        ));
+    }
+    let board_repository: Arc<dyn BoardRepository> = match FileBoardRepository::in_data_dir() {
+        Some(repository) => Arc::new(repository),
+        None => Arc::new(InMemoryBoardRepository::default()),
+    };
+    Ok((
+        Arc::new(InMemoryGameRepository::default()),
+        board_repository,
+    ))
+}
+
+const SYSTEM_WORD_LIST: &str = "/usr/share/dict/words";
+
+const WORD_LIST_VARIABLES: [&str; 3] = [
+    "STRANDS_WORDS",
+    "STRANDS_ALLOW_WORDS",
+    "STRANDS_BLOCK_WORDS",
+];
+
+/// Reads the word lists when any of them is set in the environment, so they aren't shadowed
+/// by an older compile. Otherwise prefers the word list compiled by `compile-words`.
+fn dictionary() -> io::Result<Arc<dyn Dictionary>> {
+    if WORD_LIST_VARIABLES
+        .iter()
+        .any(|variable| env::var_os(variable).is_some())
+    {
+        return Ok(Arc::new(word_lists()?));
+    }
+    match FstDictionary::default_path().filter(|path| path.exists()) {
+        Some(path) => Ok(Arc::new(FstDictionary::open(path)?)),
+        None => Ok(Arc::new(word_lists()?)),
+    }
+}
+
+fn compile_words(output: Option<PathBuf>) -> io::Result<ExitCode> {
+    let Some(output) = output.or_else(FstDictionary::default_path) else {
+        eprintln!("Could not find a data directory, pass --output");
+        return Ok(ExitCode::FAILURE);
+    };
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let words = FstDictionary::compile(&word_lists()?, &output)?;
+    println!("Compiled {} words into {}", words, output.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Loads the word list at `STRANDS_WORDS`, falling back to the system one, with words from
+/// `STRANDS_ALLOW_WORDS` added and words from `STRANDS_BLOCK_WORDS` taken out. Short words
+/// are kept, as guesses are held to each game's own `min_word_length`.
+fn word_lists() -> io::Result<HashSetDictionary> {
+    let base = env::var_os("STRANDS_WORDS").or_else(|| {
+        Path::new(SYSTEM_WORD_LIST)
+            .exists()
+            .then(|| SYSTEM_WORD_LIST.into())
+    });
+    let words = |path: Option<OsString>| match path {
+        Some(path) => read_word_file(path),
+        None => Ok(vec![]),
+    };
+    Ok(HashSetDictionary::default()
+        .allow(words(base)?)
+        .allow(words(env::var_os("STRANDS_ALLOW_WORDS"))?)
+        .block(words(env::var_os("STRANDS_BLOCK_WORDS"))?))
 }
 
 struct App {
-    board: Board,
-    highlighted: domain::Position,
+    game_service: GameService,
+    view: GameView,
+    selection: Selection,
+    status: String,
+    /// Set by the first press of `G`, so a stray keypress can't end the game.
+    confirming_give_up: bool,
+    /// Set by the first press of `r`, as a revealed word can't be found afterwards.
+    confirming_reveal: bool,
+    /// Set by the first press of `R`, as undo can't bring back the progress a restart clears.
+    confirming_restart: bool,
     should_quit: bool,
 }
 
 impl App {
-    pub fn new(board: Board) -> Self {
+    pub fn new(game_service: GameService, view: GameView) -> Self {
         Self {
-            board,
-            highlighted: domain::Position { row: 0, col: 0 },
+            game_service,
+            view,
+            selection: Selection::new(domain::Position { row: 0, col: 0 }),
+            status: String::new(),
+            confirming_give_up: false,
+            confirming_reveal: false,
+            confirming_restart: false,
             should_quit: false,
         }
     }
 
-    fn run(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
+    /// Plays until the player quits, then pauses the game. A failed pause is handed back
+    /// rather than printed, as the terminal is still in raw mode.
+    async fn run(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+    ) -> io::Result<Result<(), GameServiceError>> {
         while !self.should_quit {
             self.render(terminal);
-            self.handle_events()?;
+            self.handle_events().await?;
         }
-        Ok(())
+        // Time away from an unfinished puzzle doesn't count towards solving it.
+        Ok(self.game_service.pause(&self.view.game_id).await)
     }
 
-    fn handle_events(&mut self) -> io::Result<()> {
+    async fn handle_events(&mut self) -> io::Result<()> {
         if event::poll(std::time::Duration::from_millis(50))? {
             if let Event::Key(key) = event::read()? {
                 if key.kind == event::KeyEventKind::Press {
-                    self.handle_keypress(key)
+                    self.handle_keypress(key).await
                 }
             }
         }
         Ok(())
     }
 
-    fn handle_keypress(&mut self, key: KeyEvent) {
+    async fn handle_keypress(&mut self, key: KeyEvent) {
         use KeyCode::*;
+        let confirmed_give_up = std::mem::take(&mut self.confirming_give_up);
+        let confirmed_reveal = std::mem::take(&mut self.confirming_reveal);
+        let confirmed_restart = std::mem::take(&mut self.confirming_restart);
+        let highlighted = &mut self.selection.highlighted;
         match key.code {
             Char('q') | Esc => self.should_quit = true,
-            Char('h') | Left => self.highlighted = self.highlighted.clone().left(),
-            Char('l') | Right => self.highlighted = self.highlighted.clone().right(),
-            Char('j') | Down => self.highlighted = self.highlighted.clone().down(),
-            Char('k') | Up => self.highlighted = self.highlighted.clone().up(),
+            Char('h') | Left => *highlighted = highlighted.clone().left(),
+            Char('l') | Right => *highlighted = highlighted.clone().right(),
+            Char('j') | Down => *highlighted = highlighted.clone().down(),
+            Char('k') | Up => *highlighted = highlighted.clone().up(),
+            Char('c') => self.redeem_clue().await,
+            Char('s') => self.copy_share_text(ShareStyle::Emoji),
+            Char('S') => self.copy_share_text(ShareStyle::Ascii),
+            Char('u') => self.undo().await,
+            Char('r') if confirmed_reveal => self.reveal_highlighted().await,
+            Char('r') => {
+                self.confirming_reveal = true;
+                self.status = "Press r again to reveal the word on this tile".to_string();
+            }
+            Char('G') if confirmed_give_up => self.give_up().await,
+            Char('G') => {
+                self.confirming_give_up = true;
+                self.status = "Press G again to give up and reveal every word".to_string();
+            }
+            Char('R') if confirmed_restart => self.restart().await,
+            Char('R') => {
+                self.confirming_restart = true;
+                self.status = "Press R again to start the puzzle over".to_string();
+            }
+            Enter if self.selection.ends_at_highlighted() => self.submit_path().await,
+            Char(' ') | Enter => self.select_highlighted(),
+            Backspace => {
+                self.selection.path.pop();
+            }
             _ => {}
         }
     }
 
+    fn select_highlighted(&mut self) {
+        if !self.view.board.tiles.contains(&self.selection.highlighted) {
+            self.status = "That tile is off the board".to_string();
+            return;
+        }
+        self.status = match self.selection.select_highlighted(&self.view.rules) {
+            Ok(()) => String::new(),
+            Err(SelectTileError::AlreadySelected) => "That tile is already in the path".to_string(),
+            Err(SelectTileError::NotAdjacent) => {
+                "That tile is not adjacent to the end of the path".to_string()
+            }
+        };
+    }
+
+    async fn submit_path(&mut self) {
+        let word = self.view.board.tiles.word(&self.selection.path);
+        let positions = match ContiguousPositions::new(self.selection.path.clone()) {
+            Ok(positions) => positions,
+            Err(_) => {
+                self.status = "That path is not contiguous".to_string();
+                return;
+            }
+        };
+        let guess = match Guess::new(positions, &self.view.rules) {
+            Ok(guess) => guess,
+            Err(error) => {
+                self.status = create_guess_status(&word, &error);
+                return;
+            }
+        };
+
+        match self
+            .game_service
+            .submit_guess(&self.view.game_id, guess)
+            .await
+        {
+            Ok(update) => {
+                self.status = guess_status(&word, &update.result, &self.view.rules);
+                self.view = update.view;
+            }
+            Err(error) => self.status = format!("Could not submit guess: {}", error),
+        }
+        self.selection.clear();
+    }
+
+    fn copy_share_text(&mut self, style: ShareStyle) {
+        let text = match style {
+            ShareStyle::Emoji => &self.view.share_text,
+            ShareStyle::Ascii => &self.view.ascii_share_text,
+        };
+        self.status = match text {
+            Some(text) => match copy_to_clipboard(&mut stdout(), text) {
+                Ok(()) => "Copied your result to the clipboard".to_string(),
+                Err(error) => format!("Could not copy your result: {}", error),
+            },
+            None => "Finish the puzzle to share your result".to_string(),
+        };
+    }
+
+    async fn reveal_highlighted(&mut self) {
+        let update = self
+            .game_service
+            .reveal_answer_at(&self.view.game_id, &self.selection.highlighted)
+            .await;
+        self.show_reveal(update, "Revealed the word under the cursor");
+    }
+
+    async fn give_up(&mut self) {
+        let update = self.game_service.give_up(&self.view.game_id).await;
+        self.show_reveal(update, "Revealed every remaining word");
+    }
+
+    fn show_reveal(
+        &mut self,
+        update: Result<GameUpdate<Result<(), RevealFailure>>, GameServiceError>,
+        success: &str,
+    ) {
+        match update {
+            Ok(update) => {
+                self.status = match update.result {
+                    Ok(()) => success.to_string(),
+                    Err(RevealFailure::UnknownAnswer) => "There is no word there".to_string(),
+                    Err(RevealFailure::AlreadyResolved) => {
+                        "That word is already on the board".to_string()
+                    }
+                    Err(RevealFailure::GameFinished) => "The puzzle is solved".to_string(),
+                };
+                self.view = update.view;
+            }
+            Err(error) => self.status = format!("Could not reveal: {}", error),
+        }
+    }
+
+    async fn undo(&mut self) {
+        match self.game_service.undo(&self.view.game_id).await {
+            Ok(update) => {
+                self.status = match update.result {
+                    Ok(()) => "Undid your last move".to_string(),
+                    Err(UndoFailure::NotAllowed) => "Undo is turned off for this game".to_string(),
+                    Err(UndoFailure::NothingToUndo) => "Nothing to undo".to_string(),
+                    Err(UndoFailure::GameFinished) => "The puzzle is solved".to_string(),
+                };
+                self.view = update.view;
+            }
+            Err(error) => self.status = format!("Could not undo: {}", error),
+        }
+    }
+
+    async fn restart(&mut self) {
+        match self.game_service.restart(&self.view.game_id).await {
+            Ok(update) => {
+                self.status = match update.result {
+                    Ok(()) => {
+                        self.selection.clear();
+                        "Started the puzzle over".to_string()
+                    }
+                    Err(RestartFailure::NotAllowed) => {
+                        "Restarting is turned off for this game".to_string()
+                    }
+                    Err(RestartFailure::GameFinished) => "The puzzle is solved".to_string(),
+                };
+                self.view = update.view;
+            }
+            Err(error) => self.status = format!("Could not restart: {}", error),
+        }
+    }
+
+    async fn redeem_clue(&mut self) {
+        match self.game_service.redeem_clue(&self.view.game_id).await {
+            Ok(update) => {
+                self.status = match update.result {
+                    Ok(()) => "Hint revealed".to_string(),
+                    Err(RedeemClueFailure::ClueFullyRevealed) => {
+                        "Find the hinted word before taking another hint".to_string()
+                    }
+                    Err(RedeemClueFailure::NotEnoughClueProgress) => {
+                        "Find more words to earn a hint".to_string()
+                    }
+                    Err(RedeemClueFailure::CouldNotFindClue) => "No hints left".to_string(),
+                    Err(RedeemClueFailure::HintLimitReached) => {
+                        "You have used every hint allowed".to_string()
+                    }
+                    Err(RedeemClueFailure::GameFinished) => "The puzzle is solved".to_string(),
+                };
+                self.view = update.view;
+            }
+            Err(error) => self.status = format!("Could not redeem hint: {}", error),
+        }
+    }
+
     fn render(&mut self, terminal: &mut Terminal<impl Backend>) {
         let _ = terminal.draw(|frame| {
-            frame.render_stateful_widget(&self.board, frame.size(), &mut self.highlighted)
+            let [board_area, status_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.size());
+            frame.render_stateful_widget(&self.view.board, board_area, &mut self.selection);
+            frame.render_widget(Paragraph::new(self.status.clone()), status_area);
         });
     }
 }
 
+fn create_guess_status(word: &str, error: &CreateGuessError) -> String {
+    match error {
+        CreateGuessError::TooShort => format!("{} is too short", word),
+        CreateGuessError::DiagonalStep => "Diagonal moves are not allowed".to_string(),
+    }
+}
+
+fn guess_status(
+    word: &str,
+    result: &Result<GuessSuccess, GuessFailure>,
+    rules: &GameRules,
+) -> String {
+    match result {
+        Ok(GuessSuccess::FoundAnswer(answer)) => match answer.answer_type {
+            AnswerType::Spangram => format!("SPANGRAM! {}", word),
+            AnswerType::Normal => format!("Found theme word {}", word),
+        },
+        Ok(GuessSuccess::GainedClue(progress)) => {
+            format!(
+                "{} is a word, hint progress {}/{}",
+                word, progress, rules.hint_cost
+            )
+        }
+        Err(GuessFailure::AlreadyGuessed) => format!("Already guessed {}", word),
+        Err(GuessFailure::OutOfBounds) => "That path leaves the board".to_string(),
+        Err(GuessFailure::NotRealWord) => format!("{} is not in the word list", word),
+        Err(GuessFailure::TilesAlreadyUsed) => {
+            format!("{} uses tiles from a word you already found", word)
+        }
+        Err(GuessFailure::BreaksRules(error)) => create_guess_status(word, error),
+        Err(GuessFailure::GameFinished) => "The puzzle is solved".to_string(),
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use chrono::NaiveDate;
+    use std::sync::Arc;
 
-    use crate::{domain::*, test_fixtures::*};
+    use chrono::{DateTime, NaiveDate, TimeDelta};
+
+    use crate::{
+        adapter::{NYTBoardDto, NytClientError},
+        domain::*,
+        infrastructure::{InMemoryBoardRepository, InMemoryGameRepository},
+        test_fixtures::*,
+        ui::{osc52, SelectTileError, Selection},
+        Cli,
+    };
+    use clap::Parser;
 
     #[test]
     fn finds_answer() {
@@ -103,7 +622,13 @@ mod test {
             Position::new(0, 4),
         ])
         .unwrap();
-        let answer = Answer::new(AnswerId::new(1), AnswerType::Normal, tiles.clone(), 1);
+        let answer = Answer::new(
+            AnswerId::new(1),
+            AnswerType::Spangram,
+            tiles.clone(),
+            "Hello",
+            1,
+        );
         let answers = vec![answer.clone()];
         let board = Board::from_string(
             BoardId::new(123),
@@ -111,16 +636,10 @@ mod test {
             "Try This".to_string(),
             NaiveDate::from_ymd_opt(2024, 4, 24).unwrap(),
             answers,
-            &[
-                "H".to_string(),
-                "e".to_string(),
-                "l".to_string(),
-                "l".to_string(),
-                "o".to_string(),
-            ],
+            &["Hello".to_string()],
         )
         .unwrap();
-        let guess = Guess::new(tiles).unwrap();
+        let guess = Guess::new(tiles, &GameRules::default()).unwrap();
 
         let found_answer = board.guess_is_answer(&guess);
 
@@ -131,7 +650,7 @@ mod test {
     fn says_wrong_guess_is_not_answer() {
         let answer = Answer::new(
             AnswerId::new(1),
-            AnswerType::Normal,
+            AnswerType::Spangram,
             ContiguousPositions::new(vec![
                 Position::new(0, 0),
                 Position::new(0, 1),
@@ -140,6 +659,7 @@ mod test {
                 Position::new(0, 4),
             ])
             .unwrap(),
+            "Hello",
             1,
         );
         let answers = vec![answer.clone()];
@@ -149,13 +669,7 @@ mod test {
             "Try This".to_string(),
             NaiveDate::from_ymd_opt(2024, 4, 24).unwrap(),
             answers,
-            &[
-                "H".to_string(),
-                "e".to_string(),
-                "l".to_string(),
-                "l".to_string(),
-                "o".to_string(),
-            ],
+            &["Hello".to_string()],
         )
         .unwrap();
         let guess = Guess::new(
@@ -167,6 +681,7 @@ mod test {
                 Position::new(0, 4),
             ])
             .unwrap(),
+            &GameRules::default(),
         )
         .unwrap();
 
@@ -189,6 +704,7 @@ mod test {
                 Position::new(0, 4),
             ])
             .unwrap(),
+            &GameRules::default(),
         )
         .unwrap();
 
@@ -210,6 +726,7 @@ mod test {
                 Position::new(0, 3),
             ])
             .unwrap(),
+            &GameRules::default(),
         )
         .unwrap();
 
@@ -231,6 +748,7 @@ mod test {
                 Position::new(0, 3),
             ])
             .unwrap(),
+            &GameRules::default(),
         )
         .unwrap();
 
@@ -240,6 +758,50 @@ mod test {
         assert_eq!(duplicate_guess_response, Err(GuessFailure::AlreadyGuessed))
     }
 
+    #[test]
+    fn same_word_along_another_path_is_already_guessed() {
+        let tiles = vec!["ab".to_string(), "ba".to_string()];
+        let answers = vec![answer(
+            1,
+            AnswerType::Spangram,
+            &[(0, 0), (0, 1), (1, 1), (1, 0)],
+            "abab",
+        )];
+        let board = Board::from_string(
+            BoardId::new(789),
+            "Chris".to_string(),
+            "Try This".to_string(),
+            NaiveDate::from_ymd_opt(2024, 4, 26).unwrap(),
+            answers,
+            &tiles,
+        )
+        .unwrap();
+        let mut game = Game::new(
+            board.id.clone(),
+            PlayerId::new("chrismcdonnell"),
+            GameRules::default(),
+        );
+        let dictionary = AlwaysContainsDictionary;
+        let path = |path: &[(i32, i32)]| {
+            let positions = path
+                .iter()
+                .map(|&(row, col)| Position::new(row, col))
+                .collect();
+            Guess::new(
+                ContiguousPositions::new(positions).unwrap(),
+                &GameRules::default(),
+            )
+            .unwrap()
+        };
+
+        let first = game.make_guess(path(&[(0, 0), (1, 0), (1, 1), (0, 1)]), &board, &dictionary);
+        let second = game.make_guess(path(&[(1, 1), (0, 1), (0, 0), (1, 0)]), &board, &dictionary);
+
+        assert_eq!(first, Ok(GuessSuccess::GainedClue(1)));
+        assert_eq!(second, Err(GuessFailure::AlreadyGuessed));
+        assert_eq!(game.bonus_words(), vec!["abab".to_string()]);
+    }
+
     #[test]
     fn get_clue_after_3_words() {
         let board = sample_board();
@@ -252,6 +814,7 @@ mod test {
                 Position::new(0, 3),
             ])
             .unwrap(),
+            &GameRules::default(),
         )
         .unwrap();
         let guess_2 = Guess::new(
@@ -262,6 +825,7 @@ mod test {
                 Position::new(1, 3),
             ])
             .unwrap(),
+            &GameRules::default(),
         )
         .unwrap();
         let guess_3 = Guess::new(
@@ -272,6 +836,7 @@ mod test {
                 Position::new(1, 4),
             ])
             .unwrap(),
+            &GameRules::default(),
         )
         .unwrap();
         let dictionary = AlwaysContainsDictionary;
@@ -283,7 +848,191 @@ mod test {
         let successfully_made_clue = game.redeem_clue(&board);
 
         assert_eq!(successfully_made_clue, Ok(()));
-        assert_eq!(game.active_clue, Some(spanogram_answer().into()));
+        assert_eq!(game.active_clue(), Some(&spanogram_answer().into()));
+    }
+
+    /// Replays `game` with `progress` bonus words recorded on top, as if the player had found them.
+    fn with_clue_progress(game: Game, progress: u32) -> Game {
+        let mut events = game.events().to_vec();
+        events.extend((0..progress).map(|index| {
+            GameEvent::now(GameEventKind::GuessSubmitted {
+                positions: diagonal_guess(),
+                outcome: GuessOutcome::BonusWord(format!("bonus{}", index)),
+            })
+        }));
+        Game::from_events(game.board_id, events).unwrap()
+    }
+
+    fn game_with_clue_progress(progress: u32) -> Game {
+        with_clue_progress(sample_game(), progress)
+    }
+
+    #[test]
+    fn second_clue_reveals_letter_order() {
+        let board = sample_board();
+        let mut game = game_with_clue_progress(6);
+
+        let first = game.redeem_clue(&board);
+        let tiles_only = game.active_clue().cloned().unwrap();
+        let second = game.redeem_clue(&board);
+        let ordered = game.active_clue().cloned().unwrap();
+
+        assert_eq!((first, second), (Ok(()), Ok(())));
+        assert_eq!(tiles_only.tiles_in_order(), None);
+        assert_eq!(
+            ordered.tiles_in_order(),
+            Some(spanogram_answer().positions.inner_value())
+        );
+        assert_eq!(
+            game.actions(),
+            vec![GameAction::Clue, GameAction::ClueOrder]
+        );
+        assert_eq!(game.clue_progress_counter(), 0);
+    }
+
+    #[test]
+    fn cannot_redeem_past_letter_order() {
+        let board = sample_board();
+        let mut game = game_with_clue_progress(9);
+        let _ = game.redeem_clue(&board);
+        let _ = game.redeem_clue(&board);
+
+        let third = game.redeem_clue(&board);
+
+        assert_eq!(third, Err(RedeemClueFailure::ClueFullyRevealed));
+        assert_eq!(game.clue_progress_counter(), 3);
+    }
+
+    #[test]
+    fn finding_hinted_word_clears_clue() {
+        let board = sample_board();
+        let mut game = game_with_clue_progress(3);
+        let _ = game.redeem_clue(&board);
+
+        let _ = game.make_guess(
+            Guess::new(spanogram_answer().positions, &GameRules::default()).unwrap(),
+            &board,
+            &AlwaysContainsDictionary,
+        );
+
+        assert_eq!(game.active_clue(), None);
+    }
+
+    #[test]
+    fn finding_other_word_keeps_clue() {
+        let board = sample_board();
+        let mut game = game_with_clue_progress(3);
+        let _ = game.redeem_clue(&board);
+
+        let _ = game.make_guess(
+            Guess::new(row_answer(2, 1, "world").positions, &GameRules::default()).unwrap(),
+            &board,
+            &AlwaysContainsDictionary,
+        );
+
+        assert_eq!(game.active_clue(), Some(&spanogram_answer().into()));
+    }
+
+    fn game_with_rules(rules: GameRules) -> Game {
+        Game::new(BoardId::new(123), PlayerId::new("chrismcdonnell"), rules)
+    }
+
+    fn diagonal_guess() -> ContiguousPositions {
+        ContiguousPositions::new(vec![
+            Position::new(0, 0),
+            Position::new(1, 1),
+            Position::new(2, 2),
+            Position::new(3, 3),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn rules_can_forbid_diagonals() {
+        let rules = GameRules {
+            allow_diagonals: false,
+            ..GameRules::default()
+        };
+
+        assert_eq!(
+            Guess::new(diagonal_guess(), &rules),
+            Err(CreateGuessError::DiagonalStep)
+        );
+    }
+
+    #[test]
+    fn game_rechecks_guess_against_its_rules() {
+        let board = sample_board();
+        let mut game = game_with_rules(GameRules {
+            min_word_length: 5,
+            ..GameRules::default()
+        });
+        let guess = Guess::new(diagonal_guess(), &GameRules::default()).unwrap();
+
+        let response = game.make_guess(guess, &board, &AlwaysContainsDictionary);
+
+        assert_eq!(
+            response,
+            Err(GuessFailure::BreaksRules(CreateGuessError::TooShort))
+        );
+    }
+
+    #[test]
+    fn found_tiles_can_stay_unlocked() {
+        let board = sample_board();
+        let mut game = game_with_rules(GameRules {
+            found_tiles_lock: false,
+            ..GameRules::default()
+        });
+        let _ = game.make_guess(
+            Guess::new(row_answer(2, 1, "world").positions, &GameRules::default()).unwrap(),
+            &board,
+            &AlwaysContainsDictionary,
+        );
+
+        let response = game.make_guess(
+            Guess::new(diagonal_guess(), &GameRules::default()).unwrap(),
+            &board,
+            &AlwaysContainsDictionary,
+        );
+
+        assert_eq!(response, Ok(GuessSuccess::GainedClue(1)));
+        assert_eq!(game.available_positions(&board).len(), 25);
+    }
+
+    #[test]
+    fn bonus_words_can_skip_dictionary() {
+        let board = sample_board();
+        let mut game = game_with_rules(GameRules {
+            bonus_words_need_dictionary: false,
+            ..GameRules::default()
+        });
+
+        let response = game.make_guess(
+            Guess::new(diagonal_guess(), &GameRules::default()).unwrap(),
+            &board,
+            &NeverContainsDictionary,
+        );
+
+        assert_eq!(response, Ok(GuessSuccess::GainedClue(1)));
+    }
+
+    #[test]
+    fn rules_set_hint_cost_and_limit() {
+        let board = sample_board();
+        let mut game = game_with_rules(GameRules {
+            hint_cost: 1,
+            max_hints: Some(1),
+            ..GameRules::default()
+        });
+        game = with_clue_progress(game, 2);
+
+        let first = game.redeem_clue(&board);
+        let second = game.redeem_clue(&board);
+
+        assert_eq!(first, Ok(()));
+        assert_eq!(second, Err(RedeemClueFailure::HintLimitReached));
+        assert_eq!(game.clue_progress_counter(), 1);
     }
 
     #[test]
@@ -295,61 +1044,1041 @@ mod test {
 
         assert_eq!(not_a_clue, Err(RedeemClueFailure::NotEnoughClueProgress))
     }
-}
 
-#[cfg(test)]
-mod test_fixtures {
-    use chrono::NaiveDate;
+    #[test]
+    fn rejects_word_missing_from_dictionary() {
+        let board = sample_board();
+        let mut game = sample_game();
+        let dictionary = NeverContainsDictionary;
+        let guess = Guess::new(
+            ContiguousPositions::new(vec![
+                Position::new(0, 0),
+                Position::new(0, 1),
+                Position::new(0, 2),
+                Position::new(0, 3),
+            ])
+            .unwrap(),
+            &GameRules::default(),
+        )
+        .unwrap();
 
-    use crate::domain::*;
+        let answer = game.make_guess(guess, &board, &dictionary);
 
-    pub struct AlwaysContainsDictionary;
-    impl Dictionary for AlwaysContainsDictionary {
-        fn contains_word(&self, _word: &str) -> bool {
-            true
-        }
+        assert_eq!(answer, Err(GuessFailure::NotRealWord))
     }
 
-    pub struct NeverContainsDictionary;
-    impl Dictionary for NeverContainsDictionary {
-        fn contains_word(&self, _word: &str) -> bool {
-            false
+    #[test]
+    fn fresh_game_has_not_started() {
+        assert_eq!(sample_game().state(), GameState::NotStarted)
+    }
+
+    #[test]
+    fn first_guess_starts_game() {
+        let board = sample_board();
+        let mut game = sample_game();
+
+        let _ = game.make_guess(
+            Guess::new(row_answer(2, 1, "world").positions, &GameRules::default()).unwrap(),
+            &board,
+            &AlwaysContainsDictionary,
+        );
+
+        assert_eq!(game.state(), GameState::InProgress)
+    }
+
+    #[test]
+    fn finding_every_answer_finishes_game() {
+        let board = sample_board();
+        let mut game = sample_game();
+
+        for answer in board.answers.clone() {
+            let found = game.make_guess(
+                Guess::new(answer.positions, &GameRules::default()).unwrap(),
+                &board,
+                &AlwaysContainsDictionary,
+            );
+            assert!(found.is_ok());
+        }
+
+        match game.state() {
+            GameState::Finished(summary) => {
+                assert_eq!(summary.answers_found, 5);
+                assert_eq!(summary.hints_used, 0);
+                assert_eq!(summary.non_theme_words_found, 0);
+                assert!(!summary.gave_up);
+            }
+            state => panic!("Expected a finished game, got {:?}", state),
         }
     }
 
-    pub fn spanogram_answer() -> Answer {
-        Answer::new(
-            AnswerId::new(1),
-            AnswerType::Spangram,
+    #[test]
+    fn finished_game_rejects_guesses_and_hints() {
+        let board = sample_board();
+        let mut game = game_with_clue_progress(3);
+        for answer in board.answers.clone() {
+            let _ = game.make_guess(
+                Guess::new(answer.positions, &GameRules::default()).unwrap(),
+                &board,
+                &AlwaysContainsDictionary,
+            );
+        }
+        let guess = Guess::new(
             ContiguousPositions::new(vec![
                 Position::new(0, 0),
                 Position::new(0, 1),
                 Position::new(0, 2),
                 Position::new(0, 3),
-                Position::new(0, 4),
             ])
             .unwrap(),
-            1,
+            &GameRules::default(),
         )
-    }
+        .unwrap();
 
-    pub fn sample_game() -> Game {
-        Game::new(BoardId::new(123), PlayerId::new("chrismcdonnell"))
+        assert_eq!(
+            game.make_guess(guess, &board, &AlwaysContainsDictionary),
+            Err(GuessFailure::GameFinished)
+        );
+        assert_eq!(
+            game.redeem_clue(&board),
+            Err(RedeemClueFailure::GameFinished)
+        );
     }
 
-    pub fn sample_board() -> Board {
-        let board_id = BoardId::new(123);
-        let tiles = vec![
-            "hello".to_string(),
-            "world".to_string(),
+    #[test]
+    fn rejects_guess_through_found_answer() {
+        let board = sample_board();
+        let mut game = sample_game();
+        let dictionary = AlwaysContainsDictionary;
+        let _ = game.make_guess(
+            Guess::new(row_answer(2, 1, "world").positions, &GameRules::default()).unwrap(),
+            &board,
+            &dictionary,
+        );
+        let guess = Guess::new(
+            ContiguousPositions::new(vec![
+                Position::new(0, 1),
+                Position::new(0, 2),
+                Position::new(0, 3),
+                Position::new(1, 3),
+            ])
+            .unwrap(),
+            &GameRules::default(),
+        )
+        .unwrap();
+
+        let response = game.make_guess(guess, &board, &dictionary);
+
+        assert_eq!(response, Err(GuessFailure::TilesAlreadyUsed));
+        assert_eq!(game.clue_progress_counter(), 0);
+    }
+
+    #[test]
+    fn found_answers_are_not_available() {
+        let board = sample_board();
+        let mut game = sample_game();
+        let _ = game.make_guess(
+            Guess::new(row_answer(2, 1, "world").positions, &GameRules::default()).unwrap(),
+            &board,
+            &AlwaysContainsDictionary,
+        );
+
+        let available = game.available_positions(&board);
+
+        assert_eq!(available.len(), 20);
+        assert!(available.iter().all(|position| position.row != 1));
+    }
+
+    fn solved_game(board: &Board) -> Game {
+        let mut game = game_with_clue_progress(3);
+        let _ = game.redeem_clue(board);
+        for answer in board.answers.clone() {
+            let _ = game.make_guess(
+                Guess::new(answer.positions, &GameRules::default()).unwrap(),
+                board,
+                &AlwaysContainsDictionary,
+            );
+        }
+        game
+    }
+
+    #[test]
+    fn replaying_events_rebuilds_game() {
+        let board = sample_board();
+        let mut game = sample_game();
+        let dictionary = AlwaysContainsDictionary;
+        for row in 1..4 {
+            let bonus_word =
+                ContiguousPositions::new((0..4).map(|col| Position::new(row, 3 - col)).collect())
+                    .unwrap();
+            let _ = game.make_guess(
+                Guess::new(bonus_word, &GameRules::default()).unwrap(),
+                &board,
+                &dictionary,
+            );
+        }
+        let _ = game.redeem_clue(&board);
+        let _ = game.make_guess(
+            Guess::new(spanogram_answer().positions, &GameRules::default()).unwrap(),
+            &board,
+            &dictionary,
+        );
+        let _ = game.make_guess(
+            Guess::new(diagonal_guess(), &GameRules::default()).unwrap(),
+            &board,
+            &dictionary,
+        );
+
+        let replayed = Game::replay(game.events().to_vec(), &board).unwrap();
+
+        assert_eq!(replayed, game);
+        assert_eq!(replayed.bonus_words().len(), 3);
+        assert_eq!(replayed.found_answer_ids(), vec![AnswerId::new(1)]);
+    }
+
+    #[test]
+    fn replay_requires_the_game_to_be_created_first() {
+        let board = sample_board();
+        let game = sample_game();
+
+        let replayed = Game::replay(game.events()[1..].to_vec(), &board);
+
+        assert_eq!(replayed, Err(ReplayError::NotCreatedFirst));
+    }
+
+    #[test]
+    fn records_rejected_guesses() {
+        let board = sample_board();
+        let mut game = sample_game();
+
+        let _ = game.make_guess(
+            Guess::new(diagonal_guess(), &GameRules::default()).unwrap(),
+            &board,
+            &NeverContainsDictionary,
+        );
+
+        assert_eq!(
+            game.events().last().map(|event| &event.kind),
+            Some(&GameEventKind::GuessSubmitted {
+                positions: diagonal_guess(),
+                outcome: GuessOutcome::Rejected(GuessFailure::NotRealWord),
+            })
+        );
+    }
+
+    #[test]
+    fn rejected_guess_can_be_tried_again() {
+        let board = sample_board();
+        let mut game = sample_game();
+        let guess = Guess::new(diagonal_guess(), &GameRules::default()).unwrap();
+
+        let rejected = game.make_guess(guess.clone(), &board, &NeverContainsDictionary);
+        let retried = game.make_guess(guess, &board, &AlwaysContainsDictionary);
+
+        assert_eq!(rejected, Err(GuessFailure::NotRealWord));
+        assert_eq!(retried, Ok(GuessSuccess::GainedClue(1)));
+    }
+
+    fn found_world(game: &mut Game, board: &Board) {
+        let _ = game.make_guess(
+            Guess::new(row_answer(2, 1, "world").positions, &GameRules::default()).unwrap(),
+            board,
+            &AlwaysContainsDictionary,
+        );
+    }
+
+    #[test]
+    fn undo_reverts_last_found_word() {
+        let board = sample_board();
+        let mut game = sample_game();
+        found_world(&mut game, &board);
+
+        let undone = game.undo();
+
+        assert_eq!(undone, Ok(()));
+        assert_eq!(game.found_answer_ids(), []);
+        assert_eq!(game.available_positions(&board).len(), 25);
+    }
+
+    #[test]
+    fn undo_restores_hint_and_progress() {
+        let board = sample_board();
+        let mut game = game_with_rules(GameRules {
+            hint_cost: 1,
+            ..GameRules::default()
+        });
+        for row in 1..4 {
+            let bonus_word =
+                ContiguousPositions::new((0..4).map(|col| Position::new(row, col)).collect())
+                    .unwrap();
+            let _ = game.make_guess(
+                Guess::new(bonus_word, &GameRules::default()).unwrap(),
+                &board,
+                &AlwaysContainsDictionary,
+            );
+        }
+        let _ = game.redeem_clue(&board);
+        let after_first_hint = game.active_clue().cloned();
+        let _ = game.redeem_clue(&board);
+
+        let _ = game.undo();
+
+        assert_eq!(game.active_clue().cloned(), after_first_hint);
+        assert_eq!(game.actions(), vec![GameAction::Clue]);
+        assert_eq!(game.clue_progress_counter(), 2);
+        let _ = game.undo();
+        assert_eq!(game.active_clue(), None);
+        assert_eq!(game.clue_progress_counter(), 3);
+    }
+
+    #[test]
+    fn undo_skips_rejected_guesses() {
+        let board = sample_board();
+        let mut game = sample_game();
+        found_world(&mut game, &board);
+        let _ = game.make_guess(
+            Guess::new(diagonal_guess(), &GameRules::default()).unwrap(),
+            &board,
+            &NeverContainsDictionary,
+        );
+
+        let _ = game.undo();
+
+        assert_eq!(game.found_answer_ids(), []);
+        assert_eq!(game.undo(), Err(UndoFailure::NothingToUndo));
+    }
+
+    #[test]
+    fn rules_can_forbid_undo() {
+        let board = sample_board();
+        let mut game = game_with_rules(GameRules {
+            allow_undo: false,
+            ..GameRules::default()
+        });
+        found_world(&mut game, &board);
+
+        assert_eq!(game.undo(), Err(UndoFailure::NotAllowed));
+    }
+
+    #[test]
+    fn restart_keeps_record_of_earlier_attempt() {
+        let board = sample_board();
+        let mut game = sample_game();
+        found_world(&mut game, &board);
+
+        let restarted = game.restart();
+
+        assert_eq!(restarted, Ok(()));
+        assert_eq!(game.state(), GameState::InProgress);
+        assert_eq!(game.found_answer_ids(), []);
+        assert_eq!(game.times_restarted(), 1);
+        assert_eq!(Game::replay(game.events().to_vec(), &board), Ok(game));
+    }
+
+    #[test]
+    fn rules_can_forbid_restarting() {
+        let board = sample_board();
+        let mut game = game_with_rules(GameRules {
+            allow_undo: false,
+            ..GameRules::default()
+        });
+        found_world(&mut game, &board);
+
+        assert_eq!(game.restart(), Err(RestartFailure::NotAllowed));
+        assert_eq!(game.times_restarted(), 0);
+    }
+
+    #[test]
+    fn finished_game_cannot_restart() {
+        let board = sample_board();
+        let mut game = sample_game();
+        let _ = game.give_up(&board);
+
+        assert_eq!(game.restart(), Err(RestartFailure::GameFinished));
+    }
+
+    #[test]
+    fn revealed_answers_are_not_found() {
+        let board = sample_board();
+        let mut game = sample_game();
+
+        let revealed = game.reveal_answer(&board, &AnswerId::new(2));
+        let guessed = game.make_guess(
+            Guess::new(row_answer(2, 1, "world").positions, &GameRules::default()).unwrap(),
+            &board,
+            &AlwaysContainsDictionary,
+        );
+
+        assert_eq!(revealed, Ok(()));
+        assert_eq!(game.revealed_answer_ids(), [AnswerId::new(2)]);
+        assert_eq!(game.found_answer_ids(), []);
+        assert_eq!(guessed, Err(GuessFailure::TilesAlreadyUsed));
+        assert_eq!(
+            game.reveal_answer(&board, &AnswerId::new(2)),
+            Err(RevealFailure::AlreadyResolved)
+        );
+    }
+
+    #[test]
+    fn giving_up_reveals_the_rest_and_finishes() {
+        let board = sample_board();
+        let mut game = sample_game();
+        found_world(&mut game, &board);
+
+        let gave_up = game.give_up(&board);
+
+        assert_eq!(gave_up, Ok(()));
+        match game.state() {
+            GameState::Finished(summary) => {
+                assert_eq!(summary.answers_found, 1);
+                assert_eq!(summary.answers_revealed, 4);
+                assert!(summary.gave_up);
+            }
+            state => panic!("Expected a finished game, got {:?}", state),
+        }
+        assert_eq!(
+            game.share_text(&board, ShareStyle::Ascii),
+            "Strands #123\n\"Try This\"\nTime: 0:00\noxxx\nx"
+        );
+        assert_eq!(game.give_up(&board), Err(RevealFailure::GameFinished));
+    }
+
+    #[test]
+    fn shares_result_as_emoji_grid() {
+        let board = sample_board();
+        let game = solved_game(&board);
+
+        let share = game.share_text(&board, ShareStyle::Emoji);
+
+        assert_eq!(share, "Strands #123\n“Try This”\n⏱️ 0:00\n💡🟡🔵🔵\n🔵🔵");
+    }
+
+    #[test]
+    fn shares_result_as_ascii() {
+        let board = sample_board();
+        let game = solved_game(&board);
+
+        let share = game.share_text(&board, ShareStyle::Ascii);
+
+        assert_eq!(share, "Strands #123\n\"Try This\"\nTime: 0:00\n?Soo\noo");
+    }
+
+    #[test]
+    fn paused_time_is_left_out_of_solve_time() {
+        let board = sample_board();
+        let at = |seconds| DateTime::from_timestamp(1_713_945_600 + seconds, 0).unwrap();
+        let found = |seconds, answer: &Answer| GameEvent {
+            at: at(seconds),
+            kind: GameEventKind::GuessSubmitted {
+                positions: answer.positions.clone(),
+                outcome: GuessOutcome::FoundAnswer(answer.clone()),
+            },
+        };
+        let mut events = vec![
+            GameEvent {
+                at: at(0),
+                kind: GameEventKind::Created {
+                    player_id: PlayerId::new("chrismcdonnell"),
+                    rules: GameRules::default(),
+                },
+            },
+            GameEvent {
+                at: at(5),
+                kind: GameEventKind::Resumed,
+            },
+            found(10, &board.answers[1]),
+            GameEvent {
+                at: at(20),
+                kind: GameEventKind::Paused,
+            },
+            GameEvent {
+                at: at(620),
+                kind: GameEventKind::Resumed,
+            },
+            GameEvent {
+                at: at(630),
+                kind: GameEventKind::HintRedeemed(board.get_next_clue(&[]).unwrap()),
+            },
+        ];
+        events.extend(
+            board
+                .answers
+                .iter()
+                .filter(|answer| answer.id != board.answers[1].id)
+                .enumerate()
+                .map(|(i, answer)| found(640 + 10 * i as i64, answer)),
+        );
+        events.push(GameEvent {
+            at: at(670),
+            kind: GameEventKind::Finished,
+        });
+
+        let game = Game::replay(events, &board).unwrap();
+
+        match game.state() {
+            GameState::Finished(summary) => {
+                assert_eq!(summary.duration, TimeDelta::seconds(665));
+                assert_eq!(summary.active_duration, TimeDelta::seconds(65));
+                assert_eq!(summary.hint_times, vec![TimeDelta::seconds(25)]);
+                assert_eq!(
+                    summary.answer_times.first(),
+                    Some(&(board.answers[1].id.clone(), TimeDelta::seconds(5)))
+                );
+                assert_eq!(
+                    summary.answer_times.last(),
+                    Some(&(board.answers[4].id.clone(), TimeDelta::seconds(65)))
+                );
+            }
+            state => panic!("Expected a finished game, got {:?}", state),
+        }
+        assert!(game
+            .share_text(&board, ShareStyle::Ascii)
+            .contains("Time: 1:05"));
+    }
+
+    #[test]
+    fn opening_a_game_starts_its_clock() {
+        let mut game = sample_game();
+
+        game.resume();
+
+        assert_eq!(game.state(), GameState::InProgress);
+        assert!(!game.clock_is_stopped());
+        game.pause();
+        assert!(game.clock_is_stopped());
+    }
+
+    #[test]
+    fn pausing_only_applies_to_games_in_progress() {
+        let board = sample_board();
+        let mut game = sample_game();
+
+        game.pause();
+        assert!(!game.is_paused());
+
+        let _ = game.make_guess(
+            Guess::new(board.answers[1].positions.clone(), &GameRules::default()).unwrap(),
+            &board,
+            &AlwaysContainsDictionary,
+        );
+        game.pause();
+        assert!(game.is_paused());
+
+        game.resume();
+        assert!(!game.is_paused());
+    }
+
+    #[test]
+    fn rules_come_from_the_command_line() {
+        let cli = Cli::try_parse_from(["strands", "--official", "--hint-cost", "5"]).unwrap();
+
+        assert_eq!(
+            cli.rules.rules(),
+            GameRules {
+                hint_cost: 5,
+                allow_undo: false,
+                ..GameRules::default()
+            }
+        );
+    }
+
+    #[test]
+    fn clipboard_uses_osc52() {
+        assert_eq!(osc52("hi"), "\x1b]52;c;aGk=\x07");
+    }
+
+    #[test]
+    fn selection_grows_along_adjacent_tiles() {
+        let mut selection = Selection::new(Position::new(0, 0));
+
+        let first = selection.select_highlighted(&GameRules::default());
+        selection.highlighted = Position::new(1, 1);
+        let second = selection.select_highlighted(&GameRules::default());
+
+        assert_eq!((first, second), (Ok(()), Ok(())));
+        assert_eq!(
+            selection.path,
+            vec![Position::new(0, 0), Position::new(1, 1)]
+        );
+        assert!(selection.ends_at_highlighted());
+    }
+
+    #[test]
+    fn selection_rejects_non_adjacent_tile() {
+        let mut selection = Selection::new(Position::new(0, 0));
+
+        let _ = selection.select_highlighted(&GameRules::default());
+        selection.highlighted = Position::new(0, 2);
+        let not_adjacent = selection.select_highlighted(&GameRules::default());
+
+        assert_eq!(not_adjacent, Err(SelectTileError::NotAdjacent));
+        assert_eq!(selection.path, vec![Position::new(0, 0)]);
+    }
+
+    #[test]
+    fn selection_rejects_tile_already_in_path() {
+        let mut selection = Selection::new(Position::new(0, 0));
+
+        let _ = selection.select_highlighted(&GameRules::default());
+        selection.highlighted = Position::new(0, 1);
+        let _ = selection.select_highlighted(&GameRules::default());
+        selection.highlighted = Position::new(0, 0);
+        let already_selected = selection.select_highlighted(&GameRules::default());
+
+        assert_eq!(already_selected, Err(SelectTileError::AlreadySelected));
+    }
+
+    #[test]
+    fn reads_word_along_path() {
+        let board = sample_board();
+        let positions = ContiguousPositions::new(vec![
+            Position::new(0, 4),
+            Position::new(1, 4),
+            Position::new(2, 4),
+            Position::new(3, 4),
+        ])
+        .unwrap();
+
+        assert_eq!(board.get_word(&positions), Some("odia".to_string()))
+    }
+
+    #[test]
+    fn imports_spangram_from_nyt_payload() {
+        let dto: NYTBoardDto =
+            serde_json::from_str(&sample_nyt_json("[[1,0],[1,1],[1,2],[1,3]]")).unwrap();
+
+        let board = Board::try_from(dto).unwrap();
+
+        assert_eq!(board.spangram().word, "EFGH");
+        assert_eq!(
+            board
+                .answers
+                .iter()
+                .map(|answer| (answer.id.0, answer.word.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(0, "IJKL"), (1, "ABCD"), (2, "EFGH")]
+        );
+    }
+
+    #[test]
+    fn reports_broken_spangram_path() {
+        let dto: NYTBoardDto =
+            serde_json::from_str(&sample_nyt_json("[[1,0],[1,2],[1,1],[1,3]]")).unwrap();
+
+        let board = Board::try_from(dto);
+
+        assert_eq!(
+            board,
+            Err(ConvertNytBoardError::BrokenPath {
+                word: "EFGH".to_string(),
+                error: CreateContiguousTilesError::Disconnected
+            })
+        );
+    }
+
+    #[test]
+    fn sample_board_is_valid() {
+        let board = three_by_four_board(vec![
+            answer(
+                1,
+                AnswerType::Spangram,
+                &[(0, 0), (0, 1), (0, 2), (0, 3)],
+                "abcd",
+            ),
+            answer(
+                2,
+                AnswerType::Normal,
+                &[(1, 0), (1, 1), (1, 2), (1, 3)],
+                "efgh",
+            ),
+            answer(
+                3,
+                AnswerType::Normal,
+                &[(2, 0), (2, 1), (2, 2), (2, 3)],
+                "ijkl",
+            ),
+        ]);
+
+        assert!(board.is_ok())
+    }
+
+    #[test]
+    fn reports_every_board_violation() {
+        let board = three_by_four_board(vec![
+            answer(
+                1,
+                AnswerType::Spangram,
+                &[(0, 0), (0, 1), (0, 2), (0, 3)],
+                "ABCD",
+            ),
+            answer(
+                2,
+                AnswerType::Normal,
+                &[(1, 0), (1, 1), (1, 2), (1, 3)],
+                "efgx",
+            ),
+            answer(3, AnswerType::Normal, &[(1, 1), (1, 2), (2, 2)], "fgk"),
+        ]);
+
+        assert_eq!(
+            board,
+            Err(vec![
+                InvalidBoard::UncoveredTiles(vec![
+                    Position::new(2, 0),
+                    Position::new(2, 1),
+                    Position::new(2, 3),
+                ]),
+                InvalidBoard::OverlappingAnswers {
+                    position: Position::new(1, 1),
+                    answer_ids: vec![AnswerId::new(2), AnswerId::new(3)],
+                },
+                InvalidBoard::OverlappingAnswers {
+                    position: Position::new(1, 2),
+                    answer_ids: vec![AnswerId::new(2), AnswerId::new(3)],
+                },
+                InvalidBoard::WordMismatch {
+                    answer_id: AnswerId::new(2),
+                    word: "efgx".to_string(),
+                    spelled: "efgh".to_string(),
+                },
+                InvalidBoard::WordTooShort(AnswerId::new(3)),
+            ])
+        )
+    }
+
+    #[test]
+    fn requires_exactly_one_spangram() {
+        let missing = three_by_four_board(vec![
+            answer(
+                1,
+                AnswerType::Normal,
+                &[(0, 0), (0, 1), (0, 2), (0, 3)],
+                "abcd",
+            ),
+            answer(
+                2,
+                AnswerType::Normal,
+                &[(1, 0), (1, 1), (1, 2), (1, 3)],
+                "efgh",
+            ),
+            answer(
+                3,
+                AnswerType::Normal,
+                &[(2, 0), (2, 1), (2, 2), (2, 3)],
+                "ijkl",
+            ),
+        ]);
+        let multiple = three_by_four_board(vec![
+            answer(
+                1,
+                AnswerType::Spangram,
+                &[(0, 0), (0, 1), (0, 2), (0, 3)],
+                "abcd",
+            ),
+            answer(
+                2,
+                AnswerType::Normal,
+                &[(1, 0), (1, 1), (1, 2), (1, 3)],
+                "efgh",
+            ),
+            answer(
+                3,
+                AnswerType::Spangram,
+                &[(2, 0), (2, 1), (2, 2), (2, 3)],
+                "ijkl",
+            ),
+        ]);
+
+        assert_eq!(missing, Err(vec![InvalidBoard::MissingSpangram]));
+        assert_eq!(
+            multiple,
+            Err(vec![InvalidBoard::MultipleSpangrams(vec![
+                AnswerId::new(1),
+                AnswerId::new(3)
+            ])])
+        );
+    }
+
+    #[test]
+    fn spangram_must_touch_opposite_edges() {
+        let board = three_by_four_board(vec![
+            answer(
+                1,
+                AnswerType::Spangram,
+                &[(0, 0), (0, 1), (1, 1), (1, 0)],
+                "abfe",
+            ),
+            answer(
+                2,
+                AnswerType::Normal,
+                &[(0, 2), (0, 3), (1, 3), (1, 2)],
+                "cdhg",
+            ),
+            answer(
+                3,
+                AnswerType::Normal,
+                &[(2, 0), (2, 1), (2, 2), (2, 3)],
+                "ijkl",
+            ),
+        ]);
+
+        assert_eq!(
+            board,
+            Err(vec![InvalidBoard::SpangramDoesNotSpan(AnswerId::new(1))])
+        )
+    }
+
+    #[tokio::test]
+    async fn resumes_existing_game() {
+        let game_service = sample_game_service().await;
+        let player_id = PlayerId::new("chrismcdonnell");
+
+        let started = game_service
+            .start_or_resume(&sample_board().print_date, &player_id)
+            .await
+            .unwrap();
+        let resumed = game_service
+            .start_or_resume(&sample_board().print_date, &player_id)
+            .await
+            .unwrap();
+
+        assert_eq!(started.game_id, resumed.game_id)
+    }
+
+    #[tokio::test]
+    async fn persists_game_after_guess() {
+        let game_service = sample_game_service().await;
+        let view = game_service
+            .start_or_resume(&sample_board().print_date, &PlayerId::new("chrismcdonnell"))
+            .await
+            .unwrap();
+        let guess = Guess::new(spanogram_answer().positions, &GameRules::default()).unwrap();
+
+        let _ = game_service.submit_guess(&view.game_id, guess).await;
+        let resumed = game_service
+            .start_or_resume(&sample_board().print_date, &PlayerId::new("chrismcdonnell"))
+            .await
+            .unwrap();
+
+        assert_eq!(resumed.board.found_answers.len(), 1)
+    }
+
+    #[tokio::test]
+    async fn cannot_guess_in_unknown_game() {
+        let game_service = sample_game_service().await;
+        let guess = Guess::new(spanogram_answer().positions, &GameRules::default()).unwrap();
+
+        let response = game_service
+            .submit_guess(&GameId::new("missing"), guess)
+            .await;
+
+        assert_eq!(response.err(), Some(GameServiceError::GameNotFound))
+    }
+
+    #[tokio::test]
+    async fn reports_missing_puzzle() {
+        let game_service = GameService::new(
+            Arc::new(InMemoryGameRepository::default()),
+            Arc::new(InMemoryBoardRepository::default()),
+            Arc::new(MissingNytClient),
+            Arc::new(AlwaysContainsDictionary),
+        );
+        let date = NaiveDate::from_ymd_opt(2099, 1, 1).unwrap();
+
+        let response = game_service
+            .start_or_resume(&date, &PlayerId::new("chrismcdonnell"))
+            .await;
+
+        assert_eq!(
+            response.err(),
+            Some(GameServiceError::CouldNotFetchBoard(
+                NytClientError::NotFound(date)
+            ))
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_fixtures {
+    use std::{collections::BTreeSet, sync::Arc};
+
+    use async_trait::async_trait;
+    use chrono::NaiveDate;
+
+    use crate::{
+        adapter::{NYTBoardDto, NytClient, NytClientError},
+        domain::*,
+        infrastructure::{InMemoryBoardRepository, InMemoryGameRepository},
+    };
+
+    pub struct AlwaysContainsDictionary;
+    impl Dictionary for AlwaysContainsDictionary {
+        fn contains_word(&self, _word: &str) -> bool {
+            true
+        }
+    }
+
+    pub struct NeverContainsDictionary;
+    impl Dictionary for NeverContainsDictionary {
+        fn contains_word(&self, _word: &str) -> bool {
+            false
+        }
+    }
+
+    /// Answers prefix lookups by scanning a sorted word list, standing in for a compiled one.
+    pub struct SortedDictionary(BTreeSet<String>);
+
+    impl SortedDictionary {
+        pub fn new(words: &[&str]) -> Self {
+            Self(words.iter().map(|word| normalize_word(word)).collect())
+        }
+    }
+
+    impl Dictionary for SortedDictionary {
+        fn contains_word(&self, word: &str) -> bool {
+            self.0.contains(&normalize_word(word))
+        }
+    }
+
+    impl PrefixDictionary for SortedDictionary {
+        fn has_prefix(&self, prefix: &str) -> bool {
+            let prefix = normalize_word(prefix);
+            self.0
+                .range(prefix.clone()..)
+                .next()
+                .is_some_and(|word| word.starts_with(&prefix))
+        }
+    }
+
+    pub struct UnreachableNytClient;
+    #[async_trait]
+    impl NytClient for UnreachableNytClient {
+        async fn by_date(&self, _date: &NaiveDate) -> Result<NYTBoardDto, NytClientError> {
+            panic!("Boards should be served from the repository in tests")
+        }
+    }
+
+    pub struct MissingNytClient;
+    #[async_trait]
+    impl NytClient for MissingNytClient {
+        async fn by_date(&self, date: &NaiveDate) -> Result<NYTBoardDto, NytClientError> {
+            Err(NytClientError::NotFound(*date))
+        }
+    }
+
+    pub async fn sample_game_service() -> GameService {
+        let game_service = GameService::new(
+            Arc::new(InMemoryGameRepository::default()),
+            Arc::new(InMemoryBoardRepository::default()),
+            Arc::new(UnreachableNytClient),
+            Arc::new(AlwaysContainsDictionary),
+        );
+        game_service.insert_board(sample_board()).await.unwrap();
+        game_service
+    }
+
+    pub fn spanogram_answer() -> Answer {
+        Answer::new(
+            AnswerId::new(1),
+            AnswerType::Spangram,
+            ContiguousPositions::new(vec![
+                Position::new(0, 0),
+                Position::new(0, 1),
+                Position::new(0, 2),
+                Position::new(0, 3),
+                Position::new(0, 4),
+            ])
+            .unwrap(),
+            "hello",
+            1,
+        )
+    }
+
+    pub fn row_answer(id: u32, row: i32, word: &str) -> Answer {
+        Answer::new(
+            AnswerId::new(id),
+            AnswerType::Normal,
+            ContiguousPositions::new((0..5).map(|col| Position::new(row, col)).collect()).unwrap(),
+            word,
+            id,
+        )
+    }
+
+    pub fn answer(id: u32, answer_type: AnswerType, path: &[(i32, i32)], word: &str) -> Answer {
+        let positions = path
+            .iter()
+            .map(|&(row, col)| Position::new(row, col))
+            .collect();
+        Answer::new(
+            AnswerId::new(id),
+            answer_type,
+            ContiguousPositions::new(positions).unwrap(),
+            word,
+            id,
+        )
+    }
+
+    pub fn three_by_four_board(answers: Vec<Answer>) -> Result<Board, Vec<InvalidBoard>> {
+        Board::from_string(
+            BoardId::new(456),
+            "Chris".to_string(),
+            "Try This".to_string(),
+            NaiveDate::from_ymd_opt(2024, 4, 25).unwrap(),
+            answers,
+            &["abcd".to_string(), "efgh".to_string(), "ijkl".to_string()],
+        )
+    }
+
+    pub fn sample_nyt_json(spangram_coords: &str) -> String {
+        format!(
+            r#"{{
+                "id": 7,
+                "printDate": "2024-04-24",
+                "editor": "Chris",
+                "spangram": "EFGH",
+                "clue": "Try This",
+                "startingBoard": ["ABCD", "EFGH", "IJKL"],
+                "solutions": ["IJKL", "ABCD", "EFGH"],
+                "themeCoords": {{
+                    "ABCD": [[0,0],[0,1],[0,2],[0,3]],
+                    "IJKL": [[2,0],[2,1],[2,2],[2,3]]
+                }},
+                "spangramCoords": {}
+            }}"#,
+            spangram_coords
+        )
+    }
+
+    pub fn sample_game() -> Game {
+        Game::new(
+            BoardId::new(123),
+            PlayerId::new("chrismcdonnell"),
+            GameRules::default(),
+        )
+    }
+
+    pub fn sample_board() -> Board {
+        board_on(123, NaiveDate::from_ymd_opt(2024, 4, 24).unwrap())
+    }
+
+    /// The sample board, published under another id and date.
+    pub fn board_on(id: u32, print_date: NaiveDate) -> Board {
+        let board_id = BoardId::new(id);
+        let tiles = vec![
+            "hello".to_string(),
+            "world".to_string(),
             "thisi".to_string(),
             "fooba".to_string(),
             "rbazo".to_string(),
         ];
-        let answers = vec![spanogram_answer()];
+        let answers = vec![
+            spanogram_answer(),
+            row_answer(2, 1, "world"),
+            row_answer(3, 2, "thisi"),
+            row_answer(4, 3, "fooba"),
+            row_answer(5, 4, "rbazo"),
+        ];
         let clue = "Try This".to_string();
         let editor = "Chris".to_string();
-        let print_date = NaiveDate::from_ymd_opt(2024, 4, 24).unwrap();
 
         Board::from_string(board_id, editor, clue, print_date, answers, &tiles).unwrap()
     }
@@ -0,0 +1,14 @@
+use std::io::{self, Write};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Copies `text` to the system clipboard using the OSC 52 escape sequence, which most
+/// terminal emulators support, including over SSH.
+pub fn copy_to_clipboard(writer: &mut impl Write, text: &str) -> io::Result<()> {
+    writer.write_all(osc52(text).as_bytes())?;
+    writer.flush()
+}
+
+pub fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
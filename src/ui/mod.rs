@@ -6,16 +6,110 @@ use ratatui::{
 
 use crate::domain;
 
+mod clipboard;
+
+pub use self::clipboard::*;
+
+pub struct GameView {
+    pub game_id: domain::GameId,
+    pub rules: domain::GameRules,
+    /// The result to post once the puzzle is solved.
+    pub share_text: Option<String>,
+    /// The same result without emoji, for places that can't show them.
+    pub ascii_share_text: Option<String>,
+    pub board: Board,
+}
+
+impl GameView {
+    pub fn new(board: &domain::Board, game: &domain::Game) -> Self {
+        let found_answers = board
+            .answers
+            .iter()
+            .filter(|answer| game.is_resolved(&answer.id))
+            .map(|answer| FoundWord {
+                answer_type: answer.answer_type.clone(),
+                positions: answer.positions.inner_value(),
+                revealed: game.revealed_answer_ids().contains(&answer.id),
+            })
+            .collect_vec();
+        let hint = game
+            .active_clue()
+            .map(|clue| clue.tiles_randomized().into_iter().collect_vec())
+            .unwrap_or_default();
+        let hint_order = game
+            .active_clue()
+            .and_then(|clue| clue.tiles_in_order())
+            .unwrap_or_default();
+
+        GameView {
+            game_id: game.id.clone(),
+            rules: game.rules.clone(),
+            share_text: game
+                .is_finished()
+                .then(|| game.share_text(board, domain::ShareStyle::Emoji)),
+            ascii_share_text: game
+                .is_finished()
+                .then(|| game.share_text(board, domain::ShareStyle::Ascii)),
+            board: Board {
+                found_answers,
+                hint,
+                hint_order,
+                available: game.available_positions(board),
+                bonus_words: game.bonus_words().to_vec(),
+                clue_progress: game.clue_progress_counter(),
+                hint_cost: game.rules.hint_cost,
+                summary: match game.state() {
+                    domain::GameState::Finished(summary) => Some(summary),
+                    _ => None,
+                },
+                ..board.clone().into()
+            },
+        }
+    }
+}
+
+pub struct FoundWord {
+    pub answer_type: domain::AnswerType,
+    pub positions: Vec<domain::Position>,
+    /// Shown after the player gave up on it rather than found.
+    pub revealed: bool,
+}
+
 pub struct Board {
     pub tiles: Tiles,
     pub theme: String,
+    pub found_answers: Vec<FoundWord>,
+    pub hint: Vec<domain::Position>,
+    /// The hinted tiles in spelling order, empty until the second hint level is redeemed.
+    pub hint_order: Vec<domain::Position>,
+    pub available: Vec<domain::Position>,
+    pub bonus_words: Vec<String>,
+    pub clue_progress: u32,
+    pub hint_cost: u32,
+    pub summary: Option<domain::GameSummary>,
+}
+
+impl Board {
+    fn found_word_at(&self, position: &domain::Position) -> Option<&FoundWord> {
+        self.found_answers
+            .iter()
+            .find(|found| found.positions.contains(position))
+    }
 }
 
 impl From<domain::Board> for Board {
     fn from(domain_board: domain::Board) -> Self {
         Board {
+            available: domain_board.tiles.all_positions(),
             tiles: domain_board.tiles.into(),
             theme: domain_board.clue,
+            found_answers: vec![],
+            hint: vec![],
+            hint_order: vec![],
+            bonus_words: vec![],
+            clue_progress: 0,
+            hint_cost: domain::GameRules::default().hint_cost,
+            summary: None,
         }
     }
 }
@@ -32,6 +126,20 @@ impl Tiles {
     pub fn height(&self) -> usize {
         self.0.len()
     }
+    pub fn contains(&self, position: &domain::Position) -> bool {
+        self.letter_at(position).is_some()
+    }
+    pub fn word(&self, positions: &[domain::Position]) -> String {
+        positions
+            .iter()
+            .filter_map(|position| self.letter_at(position))
+            .collect()
+    }
+    fn letter_at(&self, position: &domain::Position) -> Option<char> {
+        let row: usize = position.row.try_into().ok()?;
+        let col: usize = position.col.try_into().ok()?;
+        self.0.get(row)?.get(col).copied()
+    }
 }
 
 impl From<domain::Tiles> for Tiles {
@@ -46,40 +154,115 @@ impl From<domain::Tiles> for Tiles {
     }
 }
 
-impl StatefulWidget for &Tiles {
-    type State = domain::Position;
-
-    fn render(self, area: Rect, buf: &mut Buffer, highlighted_position: &mut Self::State) {
-        let horizontal_layout =
-            Layout::vertical(vec![
-                Constraint::Ratio(1, self.height().try_into().unwrap());
-                self.height()
-            ]);
-        let vertical_layout =
-            Layout::horizontal(vec![
-                Constraint::Ratio(1, self.width().try_into().unwrap());
-                self.width()
-            ]);
+pub struct Selection {
+    pub highlighted: domain::Position,
+    pub path: Vec<domain::Position>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SelectTileError {
+    AlreadySelected,
+    NotAdjacent,
+}
+
+impl Selection {
+    pub fn new(highlighted: domain::Position) -> Self {
+        Self {
+            highlighted,
+            path: vec![],
+        }
+    }
+
+    pub fn select_highlighted(&mut self, rules: &domain::GameRules) -> Result<(), SelectTileError> {
+        if self.path.contains(&self.highlighted) {
+            return Err(SelectTileError::AlreadySelected);
+        }
+        if let Some(last) = self.path.last() {
+            if !rules.are_adjacent(last, &self.highlighted) {
+                return Err(SelectTileError::NotAdjacent);
+            }
+        }
+        self.path.push(self.highlighted.clone());
+        Ok(())
+    }
+
+    pub fn ends_at_highlighted(&self) -> bool {
+        self.path.last() == Some(&self.highlighted)
+    }
+
+    pub fn clear(&mut self) {
+        self.path.clear();
+    }
+}
+
+impl Board {
+    fn tile_color(&self, position: &domain::Position, selection: &Selection) -> Option<Color> {
+        if &selection.highlighted == position {
+            return Some(Color::LightBlue);
+        }
+        if selection.path.contains(position) {
+            return Some(Color::Gray);
+        }
+        if let Some(found) = self.found_word_at(position) {
+            return match (found.revealed, &found.answer_type) {
+                (true, _) => Some(Color::Red),
+                (false, domain::AnswerType::Spangram) => Some(Color::Yellow),
+                (false, domain::AnswerType::Normal) => Some(Color::Cyan),
+            };
+        }
+        if self.hint.contains(position) {
+            return Some(Color::Magenta);
+        }
+        None
+    }
+
+    fn render_tiles(&self, area: Rect, buf: &mut Buffer, selection: &Selection) {
+        let horizontal_layout = Layout::vertical(vec![
+            Constraint::Ratio(
+                1,
+                self.tiles.height().try_into().unwrap()
+            );
+            self.tiles.height()
+        ]);
+        let vertical_layout = Layout::horizontal(vec![
+            Constraint::Ratio(
+                1,
+                self.tiles.width().try_into().unwrap()
+            );
+            self.tiles.width()
+        ]);
 
         let cells = horizontal_layout
             .split(area)
-            .into_iter()
+            .iter()
             .map(|&row| vertical_layout.split(row).to_vec())
             .collect_vec();
 
         for (i, row) in cells.iter().enumerate() {
             for (j, cell) in row.iter().enumerate() {
-                let tile = self.0.get(i).unwrap().get(j).unwrap();
-                let i: i32 = i.try_into().unwrap();
-                let j: i32 = j.try_into().unwrap();
-                let block = if highlighted_position.row == i && highlighted_position.col == j {
-                    Block::default()
-                        .padding(Padding::new(0, 0, ((cell.height) / 2) - 1, 0))
-                        .bg(Color::LightBlue)
+                let tile = self.tiles.0.get(i).unwrap().get(j).unwrap();
+                let position = domain::Position::from_usize(i, j);
+                let block =
+                    Block::default().padding(Padding::new(0, 0, ((cell.height) / 2) - 1, 0));
+                let block = match self.tile_color(&position, selection) {
+                    Some(color) => block.bg(color),
+                    None => block,
+                };
+                let label = match self
+                    .hint_order
+                    .iter()
+                    .position(|hinted| hinted == &position)
+                {
+                    Some(index) => format!("{} {}", tile, index + 1),
+                    None => tile.to_string(),
+                };
+                // Letters of words that were already found can't be reused, so fade them out.
+                let text = if self.available.contains(&position) {
+                    Text::raw(label)
                 } else {
-                    Block::default().padding(Padding::new(0, 0, ((cell.height) / 2) - 1, 0))
+                    Text::raw(label).dim()
                 };
-                Paragraph::new(Text::raw(tile.to_string()))
+                Paragraph::new(text)
                     .block(block)
                     .alignment(Alignment::Center)
                     .render(*cell, buf)
@@ -88,17 +271,46 @@ impl StatefulWidget for &Tiles {
     }
 }
 
+fn restarted(restarts: u32) -> String {
+    match restarts {
+        0 => String::new(),
+        1 => "\nRestarted once".to_string(),
+        restarts => format!("\nRestarted {} times", restarts),
+    }
+}
+
 impl StatefulWidget for &Board {
-    type State = domain::Position;
+    type State = Selection;
 
-    fn render(self, area: Rect, buf: &mut Buffer, position: &mut Self::State) {
+    fn render(self, area: Rect, buf: &mut Buffer, selection: &mut Self::State) {
         // let highlight_symbol = ">";
         // let blank_symbol = " ".repeat(highlight_symbol.width());
         let vertical_split = Layout::horizontal([Constraint::Percentage(50); 2]);
         let [theme_area, tile_area] = vertical_split.areas(area);
-        self.tiles.render(tile_area, buf, position);
+        self.render_tiles(tile_area, buf, selection);
 
-        Paragraph::new(Text::raw(self.theme.clone()))
+        let progress = match &self.summary {
+            Some(summary) => format!(
+                "{} {}!\n{} theme words, {} revealed, {} hints, {} other words{}\n\nPress s to copy your result, or S for plain text",
+                if summary.gave_up {
+                    "Gave up after"
+                } else {
+                    "Solved in"
+                },
+                domain::format_duration(summary.active_duration),
+                summary.answers_found,
+                summary.answers_revealed,
+                summary.hints_used,
+                summary.non_theme_words_found,
+                restarted(summary.restarts)
+            ),
+            None => format!("Hint progress: {}/{}", self.clue_progress, self.hint_cost),
+        };
+        let mut text = format!("{}\n\n{}", self.theme, progress);
+        if !self.bonus_words.is_empty() {
+            text.push_str(&format!("\n\nOther words: {}", self.bonus_words.join(", ")));
+        }
+        Paragraph::new(Text::raw(text))
             .block(
                 Block::default()
                     .title("TODAY'S THEME")